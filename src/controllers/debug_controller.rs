@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    utils::{
+        extract::ApiJson,
+        helpers::{is_sensitive_header, redact_sensitive, REDACTED},
+        i18n::Locale,
+    },
+    views::response::ApiResponse,
+};
+
+/// Returns a router containing the diagnostic routes. Only mounted when `DEBUG_ROUTES=true`.
+pub fn routes() -> Router {
+    Router::new().route("/echo", post(echo))
+}
+
+#[derive(Serialize)]
+struct Echo {
+    content_type: Option<String>,
+    headers: BTreeMap<String, String>,
+    body: Value,
+}
+
+// Echo back what the server parsed from the request
+async fn echo(
+    locale: Locale,
+    headers: HeaderMap,
+    ApiJson(body): ApiJson<Value>,
+) -> (StatusCode, Json<ApiResponse>) {
+    let mut echoed: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in &headers {
+        // Credentials are masked like sensitive body fields
+        let value = if is_sensitive_header(name) {
+            REDACTED.into()
+        } else {
+            String::from_utf8_lossy(value.as_bytes())
+        };
+        echoed
+            .entry(name.to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert_with(|| value.into_owned());
+    }
+
+    let echo = Echo {
+        content_type: echoed.get(CONTENT_TYPE.as_str()).cloned(),
+        headers: echoed,
+        // Echoed bodies can end up in proxy or client logs
+        body: redact_sensitive(&body),
+    };
+    ApiResponse::success(locale.translate("Request echo"), Some(echo), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn echo_masks_credentials_in_headers_and_body() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("authorization", "Bearer secret"),
+            ("proxy-authorization", "Basic c2VjcmV0"),
+            ("cookie", "session=secret"),
+            ("x-api-key", "secret"),
+            ("x-trace", "visible"),
+        ] {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+
+        let (status, Json(response)) = echo(
+            Locale::En,
+            headers,
+            ApiJson(json!({ "email": "a@b.c", "password": "secret" })),
+        )
+        .await;
+        let data = response.data.unwrap();
+
+        assert_eq!(status, StatusCode::OK);
+        for name in [
+            "authorization",
+            "proxy-authorization",
+            "cookie",
+            "x-api-key",
+        ] {
+            assert_eq!(data["headers"][name], REDACTED);
+        }
+        assert_eq!(data["headers"]["x-trace"], "visible");
+        assert_eq!(
+            data["body"],
+            json!({ "email": "a@b.c", "password": REDACTED })
+        );
+        assert!(!data.to_string().contains("secret"));
+    }
+}
@@ -1 +1,2 @@
+pub mod debug_controller;
 pub mod user_controller;
@@ -1,6 +1,15 @@
-use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+use axum::{http::StatusCode, routing::get, Json, Router};
+use serde_json::json;
 
-use crate::{models::user::User, views::response::ApiResponse};
+use crate::{
+    models::user::User,
+    utils::{
+        extract::ApiPath,
+        i18n::Locale,
+        pagination::{PageRequest, SortDirection},
+    },
+    views::response::ApiResponse,
+};
 
 /// Returns a router containing all routes for the user controller.
 pub fn routes() -> Router {
@@ -9,16 +18,124 @@ pub fn routes() -> Router {
         .route("/:id", get(show))
 }
 
-async fn index() -> (StatusCode, Json<ApiResponse>) {
-    ApiResponse::success("List of users", Some(()), Some(StatusCode::CREATED))
+async fn index(locale: Locale, page: PageRequest<User>) -> (StatusCode, Json<ApiResponse>) {
+    // Simulate a users table
+    let mut users: Vec<User> = ["John Doe", "Jane Doe", "Ada Lovelace", "Alan Turing"]
+        .into_iter()
+        .zip(1..)
+        .map(|(name, id)| User {
+            id,
+            name: name.to_string(),
+        })
+        .collect();
+
+    if let Some(pattern) = page.search_pattern() {
+        users.retain(|user| ilike(&user.name, &pattern));
+    }
+    if let Some((field, direction)) = page.sort {
+        match field {
+            "name" => users.sort_by(|a, b| a.name.cmp(&b.name)),
+            _ => users.sort_by_key(|user| user.id),
+        }
+        if direction == SortDirection::Desc {
+            users.reverse();
+        }
+    }
+    let pagination = page.info(users.len() as u64);
+    let users: Vec<User> = users
+        .into_iter()
+        .skip(page.offset() as usize)
+        .take(page.per_page as usize)
+        .collect();
+
+    ApiResponse::success(
+        locale.translate("List of users"),
+        Some(json!({ "users": users, "pagination": pagination })),
+        Some(StatusCode::CREATED),
+    )
 }
 
-async fn show(Path(id): Path<u32>) -> (StatusCode, Json<ApiResponse>) {
+// Simulate `name ILIKE pattern ESCAPE '\'`
+fn ilike(value: &str, pattern: &str) -> bool {
+    fn matches(value: &[char], pattern: &[char]) -> bool {
+        match pattern {
+            [] => value.is_empty(),
+            ['%', rest @ ..] => (0..=value.len()).any(|skip| matches(&value[skip..], rest)),
+            ['_', rest @ ..] => !value.is_empty() && matches(&value[1..], rest),
+            ['\\', literal, rest @ ..] | [literal, rest @ ..] => {
+                value.first() == Some(literal) && matches(&value[1..], rest)
+            }
+        }
+    }
+
+    let value: Vec<char> = value.to_lowercase().chars().collect();
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    matches(&value, &pattern)
+}
+
+async fn show(locale: Locale, ApiPath(id): ApiPath<u32>) -> (StatusCode, Json<ApiResponse>) {
     // Simulate a user found
     let user = User {
         id,
         name: "John Doe".to_string(),
     };
     // Return a success response
-    ApiResponse::success("User found", Some(user), None)
+    ApiResponse::success(locale.translate("User found"), Some(user), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+    };
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    async fn list(query: &str) -> (StatusCode, Value) {
+        let response = routes()
+            .oneshot(
+                Request::get(format!("/?{query}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn names(body: &Value) -> Vec<&str> {
+        body["data"]["users"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| user["name"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn search_matches_case_insensitive_substrings() {
+        let (_, body) = list("search=DOE").await;
+        assert_eq!(names(&body), ["John Doe", "Jane Doe"]);
+    }
+
+    #[tokio::test]
+    async fn wildcard_search_terms_match_literally() {
+        for term in ["%25%25%25", "_", "J%25e"] {
+            let (_, body) = list(&format!("search={term}")).await;
+            assert!(names(&body).is_empty(), "{term}");
+            assert_eq!(body["data"]["pagination"]["total"], 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_search_term_is_a_422() {
+        let (status, body) = list(&format!("search={}", "%25".repeat(10_000))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "invalid_pagination");
+        assert!(body["data"]["search"].is_string());
+    }
 }
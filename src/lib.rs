@@ -1,11 +1,22 @@
 mod controllers;
+mod middlewares;
 mod models;
-mod routes;
+pub mod routes;
 mod utils;
 mod views;
 
+use std::net::SocketAddr;
+
+use axum::{extract::Request, middleware, ServiceExt};
+use tower::Layer;
+
 pub async fn run() {
-    let app = routes::create_routes();
+    // Wraps the router (rather than Router::layer) so paths are rewritten before routing
+    let app = middleware::from_fn_with_state(
+        utils::config::trailing_slash(),
+        middlewares::trailing_slash::strip_trailing_slash,
+    )
+    .layer(routes::create_routes());
 
     // run our app with hyper, listening globally on port 3000
     let listener = tokio::net::TcpListener::bind(format!(
@@ -15,5 +26,75 @@ pub async fn run() {
     ))
     .await
     .unwrap();
-    axum::serve(listener, app).await.unwrap();
+    log_startup_banner(listener.local_addr().unwrap());
+
+    // Expose the peer address so handlers can resolve the client IP
+    axum::serve(
+        listener,
+        ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(app),
+    )
+    .await
+    .unwrap();
+}
+
+/// Emits one structured `info` event describing what started. Only non-secret
+/// settings are included.
+fn log_startup_banner(addr: SocketAddr) {
+    use utils::config;
+
+    tracing::info!(
+        addr = %addr,
+        env = config::app_env(),
+        version = env!("CARGO_PKG_VERSION"),
+        commit = env!("GIT_COMMIT_HASH"),
+        envelope = ?config::api_envelope(),
+        debug_routes = config::debug_routes(),
+        maintenance = ?config::maintenance_mode(),
+        trailing_slash = ?config::trailing_slash(),
+        trust_proxy = config::trust_proxy(),
+        trusted_proxy_hops = config::trusted_proxy_hops(),
+        max_concurrent_per_ip = config::max_concurrent_requests_per_ip(),
+        "server started"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::capture::CapturedEvents;
+    use tracing::Level;
+
+    #[test]
+    fn startup_banner_is_one_event_with_only_non_secret_settings() {
+        let (events, _guard) = CapturedEvents::install();
+        log_startup_banner("127.0.0.1:4000".parse().unwrap());
+
+        let events = events.events();
+        assert_eq!(events.len(), 1);
+        let banner = &events[0];
+        assert_eq!(banner.level, Level::INFO);
+        assert_eq!(banner.fields["message"], "server started");
+        assert_eq!(banner.fields["addr"], "127.0.0.1:4000");
+        assert_eq!(banner.fields["version"], env!("CARGO_PKG_VERSION"));
+
+        // Pinning the field list keeps a secret from being added unnoticed
+        let fields: Vec<&str> = banner.fields.keys().map(String::as_str).collect();
+        assert_eq!(
+            fields,
+            [
+                "addr",
+                "commit",
+                "debug_routes",
+                "env",
+                "envelope",
+                "maintenance",
+                "max_concurrent_per_ip",
+                "message",
+                "trailing_slash",
+                "trust_proxy",
+                "trusted_proxy_hops",
+                "version",
+            ]
+        );
+    }
 }
@@ -3,5 +3,6 @@ use apis::run;
 
 #[tokio::main]
 async fn main() {
+    tracing_subscriber::fmt().init();
     run().await;
 }
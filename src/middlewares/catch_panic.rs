@@ -0,0 +1,181 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use axum::{
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::FutureExt;
+use serde_json::json;
+
+use crate::{
+    middlewares::request_id::RequestId,
+    utils::i18n::Locale,
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+thread_local! {
+    /// Backtrace of the latest panic on this thread, recorded by the panic hook.
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+/// Chains a panic hook that records a backtrace, since the unwound payload no
+/// longer knows where the panic happened. The previous hook still runs.
+fn install_backtrace_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            PANIC_BACKTRACE.with(|slot| *slot.borrow_mut() = Some(Backtrace::force_capture()));
+            previous(info);
+        }));
+    });
+}
+
+/// Turns a panicking handler into a clean 500 envelope instead of a dropped
+/// connection. The rest of the stack is polled in place rather than spawned, so
+/// it is still cancelled when the client disconnects, and the panic is caught on
+/// the thread that recorded its backtrace.
+pub async fn catch_panic(locale: Locale, request: Request, next: Next) -> Response {
+    install_backtrace_hook();
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|RequestId(id)| id.clone());
+
+    match AssertUnwindSafe(next.run(request)).catch_unwind().await {
+        Ok(response) => response,
+        Err(payload) => {
+            let backtrace = PANIC_BACKTRACE
+                .with(|slot| slot.borrow_mut().take())
+                .map_or_else(|| "unavailable".to_string(), |trace| trace.to_string());
+            tracing::error!(
+                request_id = request_id.as_deref().unwrap_or("-"),
+                method = %method,
+                path,
+                panic = panic_message(payload),
+                backtrace,
+                "handler panicked"
+            );
+
+            // Echo the id in the body too, so a bug report can quote it directly
+            let details = request_id.map(|request_id| json!({ "request_id": request_id }));
+            ApiResponse::error_with_details(ErrorCode::InternalServerError, locale, details)
+                .into_response()
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().map_or_else(
+            || "unknown panic payload".to_string(),
+            |message| message.to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{middlewares::request_id::propagate_request_id, utils::capture::CapturedEvents};
+    use axum::{
+        body::{to_bytes, Body},
+        http::StatusCode,
+        middleware,
+        routing::get,
+        Router,
+    };
+    use serde_json::Value;
+    use std::{
+        future::pending,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tower::ServiceExt;
+    use tracing::Level;
+
+    async fn explode() -> &'static str {
+        panic!("deliberate failure")
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/panic", get(explode))
+            .layer(middleware::from_fn(catch_panic))
+            .layer(middleware::from_fn(propagate_request_id))
+    }
+
+    #[tokio::test]
+    async fn panicking_route_returns_a_500_envelope() {
+        let (events, _guard) = CapturedEvents::install();
+        let response = app()
+            .oneshot(Request::get("/panic").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let request_id = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "internal_error");
+        assert_eq!(body["data"]["request_id"], request_id.as_str());
+
+        let logged = events.with_message("handler panicked");
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].level, Level::ERROR);
+        assert_eq!(logged[0].fields["panic"], "deliberate failure");
+        assert_eq!(logged[0].fields["path"], "/panic");
+        assert_eq!(logged[0].fields["request_id"], request_id);
+        assert!(logged[0].fields["backtrace"].contains("explode"));
+    }
+
+    /// Flags when dropped, i.e. when the handler future is cancelled.
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn dropping_the_request_cancels_the_handler() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&dropped);
+        let app = Router::new()
+            .route(
+                "/hang",
+                get(move || {
+                    let flag = DropFlag(Arc::clone(&flag));
+                    async move {
+                        let _flag = flag;
+                        pending::<()>().await;
+                    }
+                }),
+            )
+            .layer(middleware::from_fn(catch_panic));
+
+        // Simulates a client disconnecting mid-request
+        let request = app.oneshot(Request::get("/hang").body(Body::empty()).unwrap());
+        assert!(tokio::time::timeout(Duration::from_millis(20), request)
+            .await
+            .is_err());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,190 @@
+use axum::{
+    extract::{Request, State},
+    http::{
+        header::{
+            ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+            ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE,
+            ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+        },
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::utils::config::{AllowedOrigins, CorsConfig};
+
+const ALLOWED_METHODS: &str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+
+/// The `Access-Control-Allow-Origin` value for a request origin, if it is allowed.
+fn allow_origin(config: &CorsConfig, origin: &HeaderValue) -> Option<HeaderValue> {
+    match &config.allowed_origins {
+        AllowedOrigins::Any => Some(HeaderValue::from_static("*")),
+        AllowedOrigins::List(list) => list.contains(origin).then(|| origin.clone()),
+    }
+}
+
+fn apply(config: &CorsConfig, allow_origin: HeaderValue, headers: &mut HeaderMap) {
+    headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+    if config.allow_credentials {
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            HeaderValue::from_static("true"),
+        );
+    }
+}
+
+/// Answers preflight requests and adds CORS headers to responses for allowed origins.
+pub async fn handle_cors(
+    State(config): State<&'static CorsConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let allow_origin = request
+        .headers()
+        .get(ORIGIN)
+        .and_then(|origin| allow_origin(config, origin));
+    let is_preflight = request.method() == Method::OPTIONS
+        && request.headers().contains_key(ORIGIN)
+        && request
+            .headers()
+            .contains_key(ACCESS_CONTROL_REQUEST_METHOD);
+
+    let mut response = if is_preflight {
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        // A disallowed origin gets no CORS headers, so the browser blocks the request
+        if let Some(allow_origin) = allow_origin {
+            let headers = response.headers_mut();
+            apply(config, allow_origin, headers);
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_METHODS,
+                HeaderValue::from_static(ALLOWED_METHODS),
+            );
+            if let Some(requested) = request.headers().get(ACCESS_CONTROL_REQUEST_HEADERS) {
+                headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, requested.clone());
+            }
+            if let Some(max_age) = config.max_age_seconds {
+                headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age));
+            }
+        }
+        response
+    } else {
+        let mut response = next.run(request).await;
+        if let Some(allow_origin) = allow_origin {
+            apply(config, allow_origin, response.headers_mut());
+        }
+        response
+    };
+
+    // The answer depends on these request headers, whatever the outcome, so caches
+    // must never serve one origin's response to another
+    let headers = response.headers_mut();
+    if matches!(config.allowed_origins, AllowedOrigins::List(_)) {
+        headers.append(VARY, HeaderValue::from_static("origin"));
+    }
+    if is_preflight {
+        headers.append(
+            VARY,
+            HeaderValue::from_static("access-control-request-headers"),
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(config: CorsConfig) -> Router {
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                &*Box::leak(Box::new(config)),
+                handle_cors,
+            ))
+    }
+
+    fn list_config() -> CorsConfig {
+        CorsConfig::parse("https://app.example", true, Some(600)).unwrap()
+    }
+
+    fn preflight(origin: &str) -> Request {
+        Request::options("/")
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn vary(response: &Response) -> Vec<&str> {
+        response
+            .headers()
+            .get_all(VARY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn preflight_carries_max_age_and_credentials() {
+        let response = app(list_config())
+            .oneshot(preflight("https://app.example"))
+            .await
+            .unwrap();
+        let headers = response.headers();
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "https://app.example");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[ACCESS_CONTROL_MAX_AGE], "600");
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+        assert_eq!(
+            vary(&response),
+            ["origin", "access-control-request-headers"]
+        );
+    }
+
+    #[tokio::test]
+    async fn max_age_and_credentials_are_omitted_unless_configured() {
+        let config = CorsConfig::parse("https://app.example", false, None).unwrap();
+        let response = app(config)
+            .oneshot(preflight("https://app.example"))
+            .await
+            .unwrap();
+
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_MAX_AGE));
+        assert!(!response
+            .headers()
+            .contains_key(ACCESS_CONTROL_ALLOW_CREDENTIALS));
+    }
+
+    #[tokio::test]
+    async fn responses_vary_on_origin_even_when_not_allowed() {
+        let response = app(list_config())
+            .oneshot(preflight("https://evil.example"))
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(
+            vary(&response),
+            ["origin", "access-control-request-headers"]
+        );
+
+        let response = app(list_config())
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(vary(&response), ["origin"]);
+    }
+
+    #[test]
+    fn credentials_with_wildcard_origin_are_rejected() {
+        assert!(CorsConfig::parse("*", true, None).is_err());
+        assert!(CorsConfig::parse("*", false, None).is_ok());
+        assert!(CorsConfig::parse("https://app.example", true, None).is_ok());
+    }
+}
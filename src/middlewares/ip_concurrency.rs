@@ -0,0 +1,216 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    utils::{helpers::ClientIp, i18n::Locale},
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+/// Number of in-flight requests per client IP, capped at `limit` (`0` disables the cap).
+pub struct InFlight {
+    limit: usize,
+    counts: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl InFlight {
+    pub fn new(limit: usize) -> Self {
+        InFlight {
+            limit,
+            counts: Mutex::default(),
+        }
+    }
+
+    fn try_acquire(self: &Arc<Self>, ip: IpAddr) -> Option<InFlightGuard> {
+        let mut counts = self.counts.lock().unwrap();
+        let count = counts.entry(ip).or_insert(0);
+        if *count >= self.limit {
+            return None;
+        }
+        *count += 1;
+        Some(InFlightGuard {
+            in_flight: Arc::clone(self),
+            ip,
+        })
+    }
+}
+
+/// Releases the slot when dropped, so it is returned even if the handler panics
+/// or the client disconnects mid-request.
+struct InFlightGuard {
+    in_flight: Arc<InFlight>,
+    ip: IpAddr,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let mut counts = self.in_flight.counts.lock().unwrap();
+        if let Some(count) = counts.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.ip);
+            }
+        }
+    }
+}
+
+/// Rejects a request with 429 when its client IP already has the configured
+/// number of requests in flight (`MAX_CONCURRENT_REQUESTS_PER_IP`).
+pub async fn limit_ip_concurrency(
+    State(in_flight): State<Arc<InFlight>>,
+    locale: Locale,
+    client_ip: Option<ClientIp>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (Some(ClientIp(ip)), true) = (client_ip, in_flight.limit > 0) else {
+        return next.run(request).await;
+    };
+
+    match in_flight.try_acquire(ip) {
+        Some(_guard) => next.run(request).await,
+        None => ApiResponse::error(ErrorCode::TooManyConcurrentRequests, locale).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middlewares::catch_panic::catch_panic;
+    use axum::{
+        body::Body, extract::ConnectInfo, http::StatusCode, middleware, routing::get, Router,
+    };
+    use std::{
+        future::pending,
+        net::SocketAddr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use tokio::sync::Semaphore;
+    use tower::ServiceExt;
+
+    const LIMIT: usize = 2;
+
+    fn request(uri: &str, peer: &str) -> Request {
+        let mut request = Request::get(uri).body(Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        request
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_over_the_cap_are_rejected() {
+        // `/slow` signals `entered` and then holds its slot until `release` opens
+        let entered = Arc::new(Semaphore::new(0));
+        let release = Arc::new(Semaphore::new(0));
+        let app = Router::new()
+            .route("/fast", get(|| async { "fast" }))
+            .route(
+                "/slow",
+                get({
+                    let (entered, release) = (Arc::clone(&entered), Arc::clone(&release));
+                    move || async move {
+                        entered.add_permits(1);
+                        release.acquire().await.unwrap().forget();
+                        "slow"
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                Arc::new(InFlight::new(LIMIT)),
+                limit_ip_concurrency,
+            ));
+
+        let held: Vec<_> = (0..LIMIT)
+            .map(|_| tokio::spawn(app.clone().oneshot(request("/slow", "10.0.0.1:5000"))))
+            .collect();
+        entered.acquire_many(LIMIT as u32).await.unwrap().forget();
+
+        let over = app.clone().oneshot(request("/fast", "10.0.0.1:5001")).await;
+        assert_eq!(over.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+        let other_ip = app.clone().oneshot(request("/fast", "10.0.0.2:5000")).await;
+        assert_eq!(other_ip.unwrap().status(), StatusCode::OK);
+
+        release.add_permits(LIMIT);
+        for response in held {
+            assert_eq!(response.await.unwrap().unwrap().status(), StatusCode::OK);
+        }
+        let freed = app.oneshot(request("/fast", "10.0.0.1:5002")).await;
+        assert_eq!(freed.unwrap().status(), StatusCode::OK);
+    }
+
+    /// Counts handlers that are still running; decremented when the handler is dropped.
+    struct Running(Arc<AtomicUsize>);
+
+    impl Drop for Running {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn disconnected_requests_stop_their_handlers_and_free_the_slot() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let entered = Arc::new(Semaphore::new(0));
+        let app = Router::new()
+            .route("/fast", get(|| async { "fast" }))
+            .route(
+                "/hang",
+                get({
+                    let (running, entered) = (Arc::clone(&running), Arc::clone(&entered));
+                    move || {
+                        running.fetch_add(1, Ordering::SeqCst);
+                        entered.add_permits(1);
+                        let running = Running(Arc::clone(&running));
+                        async move {
+                            let _running = running;
+                            pending::<()>().await;
+                        }
+                    }
+                }),
+            )
+            // Same order as `create_routes`: the panic catcher sits inside the cap
+            .layer(middleware::from_fn(catch_panic))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(InFlight::new(LIMIT)),
+                limit_ip_concurrency,
+            ));
+
+        // Drive each request until its handler is running, then drop it the way
+        // hyper does when the client disconnects
+        for port in 0..LIMIT as u16 {
+            let hung = app
+                .clone()
+                .oneshot(request("/hang", &format!("10.0.0.1:{port}")));
+            tokio::select! {
+                response = hung => panic!("hung handler returned {:?}", response.unwrap().status()),
+                permit = entered.acquire() => permit.unwrap().forget(),
+            }
+        }
+
+        assert_eq!(running.load(Ordering::SeqCst), 0);
+        let response = app.oneshot(request("/fast", "10.0.0.1:6000")).await;
+        assert_eq!(response.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn zero_limit_disables_the_cap() {
+        let app =
+            Router::new()
+                .route("/", get(|| async { "ok" }))
+                .layer(middleware::from_fn_with_state(
+                    Arc::new(InFlight::new(0)),
+                    limit_ip_concurrency,
+                ));
+        let response = app.oneshot(request("/", "10.0.0.1:5000")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
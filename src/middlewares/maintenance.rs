@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::RETRY_AFTER, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    utils::{config::MaintenanceMode, i18n::Locale},
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+/// Paths that stay reachable during maintenance so probes can still see the deployment.
+const EXEMPT_PATHS: &[&str] = &["/version"];
+
+/// Suggested wait before retrying, in seconds.
+const RETRY_AFTER_SECONDS: u32 = 300;
+
+/// Rejects requests with 503 while maintenance `mode` is active.
+pub async fn check_maintenance(
+    State(mode): State<MaintenanceMode>,
+    locale: Locale,
+    request: Request,
+    next: Next,
+) -> Response {
+    let blocked = match mode {
+        MaintenanceMode::Off => false,
+        MaintenanceMode::ReadOnly => !matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        ),
+        MaintenanceMode::Full => true,
+    };
+    if !blocked || EXEMPT_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let mut response = ApiResponse::error(ErrorCode::Maintenance, locale).into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECONDS));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Method, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    async fn send(mode: MaintenanceMode, method: Method, uri: &str) -> Response {
+        Router::new()
+            .route(
+                "/users",
+                get(|| async { "list" }).post(|| async { "create" }),
+            )
+            .route("/version", get(|| async { "1.0" }).post(|| async { "1.0" }))
+            .layer(middleware::from_fn_with_state(mode, check_maintenance))
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    fn assert_unavailable(response: &Response) {
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[RETRY_AFTER], "300");
+    }
+
+    #[tokio::test]
+    async fn off_serves_everything() {
+        for method in [Method::GET, Method::POST] {
+            let response = send(MaintenanceMode::Off, method, "/users").await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn full_rejects_every_method() {
+        for method in [Method::GET, Method::POST] {
+            assert_unavailable(&send(MaintenanceMode::Full, method, "/users").await);
+        }
+    }
+
+    #[tokio::test]
+    async fn read_only_rejects_only_writes() {
+        let response = send(MaintenanceMode::ReadOnly, Method::GET, "/users").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_unavailable(&send(MaintenanceMode::ReadOnly, Method::POST, "/users").await);
+    }
+
+    #[tokio::test]
+    async fn exempt_paths_stay_reachable() {
+        for mode in [MaintenanceMode::Full, MaintenanceMode::ReadOnly] {
+            for method in [Method::GET, Method::POST] {
+                let response = send(mode, method, "/version").await;
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub mod catch_panic;
+pub mod cors;
+pub mod ip_concurrency;
+pub mod maintenance;
+pub mod pretty_json;
+pub mod request_id;
+pub mod request_logger;
+pub mod security_headers;
+pub mod trailing_slash;
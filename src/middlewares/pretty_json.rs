@@ -0,0 +1,118 @@
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::header::{CONTENT_LENGTH, CONTENT_TYPE},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+
+use crate::{
+    utils::i18n::Locale,
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+/// Largest response body that will be re-formatted; bigger ones pass through untouched.
+const MAX_PRETTY_BODY_BYTES: usize = 1024 * 1024;
+
+/// Pretty-prints JSON responses when asked via `?pretty=true` or `X-Pretty: true`.
+/// Only mounted when `DEBUG_ROUTES=true`. The body is re-parsed, so object keys
+/// come out sorted.
+pub async fn pretty_json(locale: Locale, request: Request, next: Next) -> Response {
+    let wants_pretty = request
+        .uri()
+        .query()
+        .is_some_and(|query| query.split('&').any(|pair| pair == "pretty=true"))
+        || request
+            .headers()
+            .get("x-pretty")
+            .is_some_and(|value| value == "true");
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !wants_pretty || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let fits = body
+        .size_hint()
+        .upper()
+        .is_some_and(|size| size <= MAX_PRETTY_BODY_BYTES as u64);
+    if !fits {
+        return Response::from_parts(parts, body);
+    }
+    // The original body is gone, so its status and headers can't be kept
+    let Ok(bytes) = to_bytes(body, MAX_PRETTY_BODY_BYTES).await else {
+        return ApiResponse::error(ErrorCode::InternalServerError, locale).into_response();
+    };
+    let pretty = serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .and_then(|value| serde_json::to_vec_pretty(&value).ok());
+
+    match pretty {
+        Some(pretty) => {
+            parts.headers.remove(CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(pretty))
+        }
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, middleware, routing::get, Json, Router};
+    use tower::ServiceExt;
+
+    async fn users() -> (StatusCode, Json<ApiResponse>) {
+        ApiResponse::success("List of users", Some(["John Doe"]), None)
+    }
+
+    async fn body(request: Request) -> String {
+        let response = Router::new()
+            .route("/users", get(users))
+            .route("/text", get(|| async { "plain\ntext" }))
+            .layer(middleware::from_fn(pretty_json))
+            .oneshot(request)
+            .await
+            .unwrap();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    fn get_request(uri: &str) -> Request {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn pretty_output_has_newlines_when_requested() {
+        let by_query = body(get_request("/users?pretty=true")).await;
+        assert!(by_query.contains("\n  \"success\": true"));
+
+        let by_header = body(
+            Request::get("/users")
+                .header("x-pretty", "true")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(by_header, by_query);
+    }
+
+    #[tokio::test]
+    async fn output_is_compact_otherwise() {
+        for uri in ["/users", "/users?pretty=false", "/users?pretty=trueish"] {
+            assert!(!body(get_request(uri)).await.contains('\n'), "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn non_json_bodies_are_untouched() {
+        assert_eq!(body(get_request("/text?pretty=true")).await, "plain\ntext");
+    }
+}
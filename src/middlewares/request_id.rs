@@ -0,0 +1,136 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+static X_CORRELATION_ID: HeaderName = HeaderName::from_static("x-correlation-id");
+
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Id correlating a request across services, available as a request extension.
+#[derive(Clone)]
+pub struct RequestId(pub String);
+
+/// Reuses an upstream `X-Request-Id`/`X-Correlation-Id` when it looks sane, otherwise
+/// generates one, and echoes it back in the `X-Request-Id` response header.
+pub async fn propagate_request_id(mut request: Request, next: Next) -> Response {
+    let incoming = [&X_REQUEST_ID, &X_CORRELATION_ID]
+        .into_iter()
+        .filter_map(|name| request.headers().get(name))
+        .filter_map(|value| value.to_str().ok())
+        .find(|value| is_valid_request_id(value))
+        .map(str::to_owned);
+    let request_id = incoming.unwrap_or_else(generate_request_id);
+
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    }
+    response
+}
+
+// Restricting the charset keeps ids from injecting fake lines or fields into logs
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+fn generate_request_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:016x}{:016x}", nanos, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    /// Sends a request with `headers` and returns (id seen by the handler, id in the response).
+    async fn round_trip(headers: &[(&HeaderName, &str)]) -> (String, String) {
+        let app = Router::new()
+            .route("/", get(|Extension(RequestId(id))| async move { id }))
+            .layer(middleware::from_fn(propagate_request_id));
+        let mut request = Request::get("/");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let header = response.headers()[&X_REQUEST_ID]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (String::from_utf8(body.to_vec()).unwrap(), header)
+    }
+
+    #[tokio::test]
+    async fn incoming_request_id_is_preserved() {
+        let (seen, echoed) = round_trip(&[(&X_REQUEST_ID, "gw-1234:abc.def_9")]).await;
+        assert_eq!(seen, "gw-1234:abc.def_9");
+        assert_eq!(echoed, "gw-1234:abc.def_9");
+    }
+
+    #[tokio::test]
+    async fn correlation_id_is_used_when_request_id_is_absent() {
+        let (seen, echoed) = round_trip(&[(&X_CORRELATION_ID, "corr-42")]).await;
+        assert_eq!((seen.as_str(), echoed.as_str()), ("corr-42", "corr-42"));
+    }
+
+    #[tokio::test]
+    async fn malformed_request_id_is_replaced() {
+        let too_long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        for malformed in ["", "id with spaces", "id\"quoted", too_long.as_str()] {
+            let (seen, echoed) = round_trip(&[(&X_REQUEST_ID, malformed)]).await;
+            assert_ne!(seen, malformed);
+            assert_eq!(seen, echoed);
+            assert!(is_valid_request_id(&seen));
+        }
+    }
+
+    #[test]
+    fn validation_rejects_log_injection_and_oversized_ids() {
+        assert!(is_valid_request_id("abc-123"));
+        assert!(is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN)));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+        assert!(!is_valid_request_id("abc\r\nlevel=ERROR"));
+        assert!(!is_valid_request_id("abc\n"));
+        assert!(!is_valid_request_id("id=1 user=admin"));
+        assert!(!is_valid_request_id(""));
+    }
+
+    #[test]
+    fn generated_ids_are_valid_and_distinct() {
+        let (first, second) = (generate_request_id(), generate_request_id());
+        assert!(is_valid_request_id(&first));
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,180 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    middlewares::request_id::RequestId,
+    utils::{config, helpers::ClientIp},
+};
+
+/// Settings for [`log_request`], normally built from `SLOW_REQUEST_MS` and `SERVER_TIMING`.
+#[derive(Clone, Copy)]
+pub struct RequestLogConfig {
+    pub slow_request_ms: u128,
+    pub server_timing: bool,
+}
+
+impl RequestLogConfig {
+    pub fn from_env() -> Self {
+        RequestLogConfig {
+            slow_request_ms: config::slow_request_ms(),
+            server_timing: config::server_timing(),
+        }
+    }
+}
+
+/// Emits one `info` event per request with the resolved client IP, status and
+/// duration, plus a `warn` event when the request exceeds `slow_request_ms`. With
+/// `server_timing` the duration is also exposed to browsers through a
+/// `Server-Timing` header.
+pub async fn log_request(
+    State(log_config): State<RequestLogConfig>,
+    client_ip: Option<ClientIp>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_owned();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched| matched.as_str().to_owned());
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map_or_else(|| "-".to_string(), |RequestId(id)| id.clone());
+    let started = Instant::now();
+
+    let mut response = next.run(request).await;
+
+    let elapsed = started.elapsed();
+    if log_config.server_timing {
+        let timing = format!("total;dur={:.3}", elapsed.as_secs_f64() * 1000.0);
+        if let Ok(value) = HeaderValue::from_str(&timing) {
+            response
+                .headers_mut()
+                .insert(HeaderName::from_static("server-timing"), value);
+        }
+    }
+
+    let elapsed_ms = elapsed.as_millis();
+    let ip = client_ip.map_or_else(|| "-".to_string(), |ClientIp(ip)| ip.to_string());
+    tracing::info!(
+        request_id,
+        client_ip = ip,
+        method = %method,
+        path,
+        status = response.status().as_u16(),
+        duration_ms = elapsed_ms,
+        "request completed"
+    );
+    if elapsed_ms > log_config.slow_request_ms {
+        tracing::warn!(
+            request_id,
+            method = %method,
+            route = route.as_deref().unwrap_or(&path),
+            duration_ms = elapsed_ms,
+            threshold_ms = log_config.slow_request_ms,
+            "slow request"
+        );
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::capture::CapturedEvents;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use std::time::Duration;
+    use tower::ServiceExt;
+    use tracing::Level;
+
+    fn app() -> Router {
+        app_with(RequestLogConfig {
+            slow_request_ms: 20,
+            server_timing: false,
+        })
+    }
+
+    fn app_with(log_config: RequestLogConfig) -> Router {
+        Router::new()
+            .route("/fast", get(|| async { "fast" }))
+            .route(
+                "/slow/:id",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(60)).await;
+                    "slow"
+                }),
+            )
+            .layer(middleware::from_fn_with_state(log_config, log_request))
+    }
+
+    async fn get_path(uri: &str) {
+        app()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn slow_request_emits_a_warning_with_route_and_duration() {
+        let (events, _guard) = CapturedEvents::install();
+        get_path("/slow/7").await;
+
+        let warnings = events.with_message("slow request");
+        assert_eq!(warnings.len(), 1);
+        let warning = &warnings[0];
+        assert_eq!(warning.level, Level::WARN);
+        assert_eq!(warning.fields["route"], "/slow/:id");
+        assert_eq!(warning.fields["threshold_ms"], "20");
+        assert!(warning.fields["duration_ms"].parse::<u128>().unwrap() >= 60);
+    }
+
+    #[tokio::test]
+    async fn fast_request_is_logged_without_a_warning() {
+        let (events, _guard) = CapturedEvents::install();
+        get_path("/fast").await;
+
+        assert!(events.with_message("slow request").is_empty());
+        let completed = events.with_message("request completed");
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].level, Level::INFO);
+        assert_eq!(completed[0].fields["path"], "/fast");
+        assert_eq!(completed[0].fields["status"], "200");
+    }
+
+    async fn server_timing_header(server_timing: bool) -> Option<String> {
+        let response = app_with(RequestLogConfig {
+            slow_request_ms: 1000,
+            server_timing,
+        })
+        .oneshot(Request::get("/fast").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+        response
+            .headers()
+            .get("server-timing")
+            .map(|value| value.to_str().unwrap().to_owned())
+    }
+
+    #[tokio::test]
+    async fn server_timing_reports_total_duration_in_milliseconds() {
+        let header = server_timing_header(true).await.unwrap();
+        let duration = header.strip_prefix("total;dur=").unwrap();
+        let (whole, fraction) = duration.split_once('.').unwrap();
+        assert!(whole.parse::<u64>().is_ok(), "{header}");
+        assert_eq!(fraction.len(), 3, "{header}");
+        assert!(fraction.bytes().all(|b| b.is_ascii_digit()), "{header}");
+    }
+
+    #[tokio::test]
+    async fn server_timing_is_absent_when_disabled() {
+        assert_eq!(server_timing_header(false).await, None);
+    }
+}
@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::STRICT_TRANSPORT_SECURITY, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::utils::{
+    config::{self, HstsConfig},
+    helpers::is_https,
+};
+
+/// Adds `Strict-Transport-Security` to responses for HTTPS requests only; sending
+/// it over plain HTTP is ignored by browsers and violates RFC 6797.
+pub async fn hsts(
+    State(hsts): State<&'static HstsConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let https = is_https(request.headers(), config::trust_proxy());
+    let mut response = next.run(request).await;
+
+    if https && hsts.max_age > 0 {
+        if let Ok(value) = HeaderValue::from_str(&hsts_directives(hsts)) {
+            response
+                .headers_mut()
+                .insert(STRICT_TRANSPORT_SECURITY, value);
+        }
+    }
+    response
+}
+
+fn hsts_directives(hsts: &HstsConfig) -> String {
+    let mut directives = format!("max-age={}", hsts.max_age);
+    if hsts.include_subdomains {
+        directives.push_str("; includeSubDomains");
+    }
+    if hsts.preload {
+        directives.push_str("; preload");
+    }
+    directives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directives(include_subdomains: bool, preload: bool) -> String {
+        hsts_directives(&HstsConfig {
+            max_age: 31_536_000,
+            include_subdomains,
+            preload,
+        })
+    }
+
+    #[test]
+    fn composes_optional_directives() {
+        assert_eq!(directives(false, false), "max-age=31536000");
+        assert_eq!(
+            directives(true, false),
+            "max-age=31536000; includeSubDomains"
+        );
+        assert_eq!(directives(false, true), "max-age=31536000; preload");
+        assert_eq!(
+            directives(true, true),
+            "max-age=31536000; includeSubDomains; preload"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use axum::{
+    extract::{Request, State},
+    http::{uri::PathAndQuery, Method, Uri},
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+};
+
+use crate::utils::config::TrailingSlash;
+
+/// `path` without its trailing slash (plus query), or `None` if it is already canonical.
+fn canonical_path_and_query(uri: &Uri) -> Option<String> {
+    let path = uri.path();
+    if path == "/" || !path.ends_with('/') {
+        return None;
+    }
+
+    // Collapse the leading run of slashes and backslashes too, so "//evil.example/"
+    // or "/\\evil.example/" can't become a protocol-relative redirect target
+    let canonical = format!(
+        "/{}",
+        path.trim_start_matches(['/', '\\']).trim_end_matches('/')
+    );
+    Some(match uri.query() {
+        Some(query) => format!("{canonical}?{query}"),
+        None => canonical,
+    })
+}
+
+/// Routes `/users/` as `/users` in `ignore` mode. Must wrap the whole router,
+/// since it has to run before routing.
+pub async fn strip_trailing_slash(
+    State(mode): State<TrailingSlash>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if mode == TrailingSlash::Ignore {
+        if let Some(path_and_query) = canonical_path_and_query(request.uri()) {
+            let mut parts = request.uri().clone().into_parts();
+            parts.path_and_query = path_and_query.parse::<PathAndQuery>().ok();
+            if let Ok(uri) = Uri::from_parts(parts) {
+                *request.uri_mut() = uri;
+            }
+        }
+    }
+    next.run(request).await
+}
+
+/// Answers `/users/` with a 308 to `/users` in `redirect` mode. Added with
+/// `Router::layer` inside the request-id, logging and CORS layers, so redirects
+/// get the same headers and log line as any other response. `OPTIONS` is never
+/// redirected because browsers reject redirected preflights.
+pub async fn redirect_trailing_slash(request: Request, next: Next) -> Response {
+    if request.method() == Method::OPTIONS {
+        return next.run(request).await;
+    }
+    match canonical_path_and_query(request.uri()) {
+        Some(path_and_query) => Redirect::permanent(&path_and_query).into_response(),
+        None => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{middlewares::cors::handle_cors, utils::config::CorsConfig};
+    use axum::{
+        body::Body,
+        http::{
+            header::{
+                ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_REQUEST_METHOD, LOCATION, ORIGIN,
+            },
+            StatusCode,
+        },
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::{Layer, ServiceExt};
+
+    fn router() -> Router {
+        Router::new().route("/version", get(|| async { "1.0" }))
+    }
+
+    async fn send(mode: TrailingSlash, request: Request) -> Response {
+        match mode {
+            TrailingSlash::Strict => router().oneshot(request).await,
+            TrailingSlash::Redirect => {
+                router()
+                    .layer(middleware::from_fn(redirect_trailing_slash))
+                    .oneshot(request)
+                    .await
+            }
+            TrailingSlash::Ignore => {
+                middleware::from_fn_with_state(mode, strip_trailing_slash)
+                    .layer(router())
+                    .oneshot(request)
+                    .await
+            }
+        }
+        .unwrap()
+    }
+
+    fn get_request(uri: &str) -> Request {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn strict_treats_trailing_slash_as_a_distinct_route() {
+        let response = send(TrailingSlash::Strict, get_request("/version/")).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = send(TrailingSlash::Strict, get_request("/version")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn redirect_sends_a_308_to_the_canonical_path() {
+        let response = send(TrailingSlash::Redirect, get_request("/version/?a=1")).await;
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers()[LOCATION], "/version?a=1");
+
+        let response = send(TrailingSlash::Redirect, get_request("/version")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn redirect_never_targets_another_host() {
+        for uri in ["//evil.example/", "/\\evil.example/", "/\\/evil.example/"] {
+            let response = send(TrailingSlash::Redirect, get_request(uri)).await;
+            assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT, "{uri}");
+            assert_eq!(response.headers()[LOCATION], "/evil.example", "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn redirect_leaves_preflights_to_cors() {
+        let cors = CorsConfig::parse("https://app.example", false, None).unwrap();
+        let app = router()
+            .layer(middleware::from_fn(redirect_trailing_slash))
+            .layer(middleware::from_fn_with_state(
+                &*Box::leak(Box::new(cors)),
+                handle_cors,
+            ));
+        let preflight = Request::options("/version/")
+            .header(ORIGIN, "https://app.example")
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(preflight).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example"
+        );
+    }
+
+    #[tokio::test]
+    async fn ignore_routes_trailing_slash_to_the_canonical_route() {
+        let response = send(TrailingSlash::Ignore, get_request("/version/")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(TrailingSlash::Ignore, get_request("/version")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+use crate::utils::pagination::Paginated;
+
 #[derive(Serialize, Deserialize)]
 pub struct User {
     pub id: u32,
     pub name: String,
 }
+
+impl Paginated for User {
+    const SORTABLE_FIELDS: &'static [&'static str] = &["id", "name"];
+}
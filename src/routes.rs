@@ -1,35 +1,313 @@
 use crate::controllers::{self};
-use crate::views::response::ApiResponse;
-use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+use crate::middlewares::{
+    catch_panic, cors, ip_concurrency, maintenance, pretty_json, request_id, request_logger,
+    security_headers, trailing_slash,
+};
+use crate::utils::{
+    config::{self, TrailingSlash},
+    extract::{ApiPath, ApiQuery},
+    i18n::Locale,
+};
+use crate::views::{error_code::ErrorCode, response::ApiResponse};
+use axum::{http::StatusCode, middleware, routing::get, Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 pub fn create_routes() -> Router {
-    Router::new()
+    build_routes(config::debug_routes())
+}
+
+fn build_routes(debug_routes: bool) -> Router {
+    let mut router = Router::new()
         .route("/", get(index))
+        .route("/version", get(version))
         .route("/errors/:code", get(simulate_error))
-        .nest("/users", controllers::user_controller::routes())
+        .nest("/users", controllers::user_controller::routes());
+
+    // Diagnostic routes are never mounted unless explicitly enabled
+    if debug_routes {
+        router = router.nest("/debug", controllers::debug_controller::routes());
+    }
+
+    router = router
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed);
+
+    // Pretty-printing is a debugging aid, so it shares the DEBUG_ROUTES gate
+    if debug_routes {
+        router = router.layer(middleware::from_fn(pretty_json::pretty_json));
+    }
+
+    // Redirects run inside the layers below so 308s still get a request id, CORS
+    // headers and a log line; `ignore` rewrites before routing instead (see `run`)
+    if config::trailing_slash() == TrailingSlash::Redirect {
+        router = router.layer(middleware::from_fn(trailing_slash::redirect_trailing_slash));
+    }
+
+    router
+        .layer(middleware::from_fn_with_state(
+            config::maintenance_mode(),
+            maintenance::check_maintenance,
+        ))
+        .layer(middleware::from_fn_with_state(
+            config::cors(),
+            cors::handle_cors,
+        ))
+        .layer(middleware::from_fn_with_state(
+            config::hsts(),
+            security_headers::hsts,
+        ))
+        .layer(middleware::from_fn(catch_panic::catch_panic))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(ip_concurrency::InFlight::new(
+                config::max_concurrent_requests_per_ip(),
+            )),
+            ip_concurrency::limit_ip_concurrency,
+        ))
+        .layer(middleware::from_fn_with_state(
+            request_logger::RequestLogConfig::from_env(),
+            request_logger::log_request,
+        ))
+        .layer(middleware::from_fn(request_id::propagate_request_id))
 }
 
 async fn index() -> &'static str {
     "Hello, World 2!"
 }
 
-// Example route: Simulate an error
-async fn simulate_error(Path(code): Path<u16>) -> (StatusCode, Json<ApiResponse>) {
-    match code {
-        422 => ApiResponse::failure(
-            "Unprocessable entity",
-            Some(StatusCode::UNPROCESSABLE_ENTITY),
-        ),
-        400 => ApiResponse::failure("Bad request", Some(StatusCode::BAD_REQUEST)),
-        404 => ApiResponse::failure("Not found", Some(StatusCode::NOT_FOUND)),
-        500 => ApiResponse::failure(
-            "Internal server error",
-            Some(StatusCode::INTERNAL_SERVER_ERROR),
-        ),
-        503 => ApiResponse::failure("Service unavailable", Some(StatusCode::SERVICE_UNAVAILABLE)),
-        501 => ApiResponse::failure("Not implemented", Some(StatusCode::NOT_IMPLEMENTED)),
-        401 => ApiResponse::failure("Unauthorized", Some(StatusCode::UNAUTHORIZED)),
-        403 => ApiResponse::failure("Forbidden", Some(StatusCode::FORBIDDEN)),
-        _ => ApiResponse::failure("Unknown error", Some(StatusCode::INTERNAL_SERVER_ERROR)),
+// Fallback for paths that match no route
+async fn not_found(locale: Locale) -> (StatusCode, Json<ApiResponse>) {
+    ApiResponse::error(ErrorCode::RouteNotFound, locale)
+}
+
+// Fallback for known paths requested with an unsupported method
+async fn method_not_allowed(locale: Locale) -> (StatusCode, Json<ApiResponse>) {
+    ApiResponse::error(ErrorCode::MethodNotAllowed, locale)
+}
+
+#[derive(Serialize)]
+struct BuildInfo {
+    version: &'static str,
+    git_commit: &'static str,
+    build_timestamp: &'static str,
+    rustc_version: &'static str,
+}
+
+// Build metadata captured at compile time by build.rs
+async fn version(locale: Locale) -> (StatusCode, Json<ApiResponse>) {
+    let info = BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: env!("GIT_COMMIT_HASH"),
+        build_timestamp: env!("BUILD_TIMESTAMP"),
+        rustc_version: env!("RUSTC_VERSION"),
+    };
+    ApiResponse::success(locale.translate("Build information"), Some(info), None)
+}
+
+/// Longest custom message a sample route will echo back.
+const MAX_SAMPLE_MESSAGE_CHARS: usize = 200;
+
+#[derive(Deserialize)]
+struct SampleQuery {
+    message: Option<String>,
+}
+
+// Example route: Simulate an error. `?message=` replaces the canonical message so
+// clients can see how a custom message travels alongside the stable `code`.
+async fn simulate_error(
+    locale: Locale,
+    ApiPath(code): ApiPath<u16>,
+    ApiQuery(query): ApiQuery<SampleQuery>,
+) -> (StatusCode, Json<ApiResponse>) {
+    let error = match code {
+        422 => ErrorCode::UnprocessableEntity,
+        400 => ErrorCode::BadRequest,
+        404 => ErrorCode::NotFound,
+        500 => ErrorCode::InternalServerError,
+        503 => ErrorCode::ServiceUnavailable,
+        501 => ErrorCode::NotImplemented,
+        401 => ErrorCode::Unauthorized,
+        403 => ErrorCode::Forbidden,
+        _ => ErrorCode::Unknown,
+    };
+    let (status, Json(mut response)) = ApiResponse::error(error, locale);
+    if let Some(message) = query.message.filter(|message| !message.trim().is_empty()) {
+        response.message = message.chars().take(MAX_SAMPLE_MESSAGE_CHARS).collect();
+    }
+    (status, Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::ApiEnvelope;
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    fn render(response: &ApiResponse, envelope: ApiEnvelope) -> Value {
+        response
+            .serialize_as(envelope, serde_json::value::Serializer)
+            .unwrap()
+    }
+
+    async fn send(router: Router, request: Request) -> (StatusCode, Value) {
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn get(uri: &str) -> (StatusCode, Value) {
+        send(
+            create_routes(),
+            Request::get(uri).body(Body::empty()).unwrap(),
+        )
+        .await
+    }
+
+    fn echo_request() -> Request {
+        Request::post("/debug/echo")
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn debug_routes_are_not_mounted_when_disabled() {
+        let (status, body) = send(build_routes(false), echo_request()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "route_not_found");
+
+        let (status, _) = send(build_routes(true), echo_request()).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn unknown_path_is_a_json_404() {
+        let (status, body) = get("/no/such/route").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["code"], "route_not_found");
+    }
+
+    #[tokio::test]
+    async fn wrong_method_on_a_known_path_is_a_json_405() {
+        for (method, uri) in [("POST", "/users"), ("DELETE", "/version")] {
+            let request = Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+            let (status, body) = send(create_routes(), request).await;
+            assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED, "{method} {uri}");
+            assert_eq!(body["success"], false, "{method} {uri}");
+            assert_eq!(body["code"], "method_not_allowed", "{method} {uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_path_parameters_use_the_error_envelope() {
+        for uri in ["/users/abc", "/errors/abc"] {
+            let (status, body) = get(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert_eq!(body["code"], "invalid_path", "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn sample_routes_echo_a_custom_message_with_the_canonical_code() {
+        for (uri, status, code) in [
+            (
+                "/errors/404?message=No%20such%20user",
+                StatusCode::NOT_FOUND,
+                "not_found",
+            ),
+            (
+                "/errors/503?message=No%20such%20user",
+                StatusCode::SERVICE_UNAVAILABLE,
+                "service_unavailable",
+            ),
+        ] {
+            let (actual_status, body) = get(uri).await;
+            assert_eq!(actual_status, status, "{uri}");
+            assert_eq!(body["message"], "No such user", "{uri}");
+            assert_eq!(body["code"], code, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn sample_message_is_capped_and_blank_is_ignored() {
+        let long = "x".repeat(MAX_SAMPLE_MESSAGE_CHARS + 50);
+        let (_, body) = get(&format!("/errors/400?message={long}")).await;
+        assert_eq!(
+            body["message"].as_str().unwrap().len(),
+            MAX_SAMPLE_MESSAGE_CHARS
+        );
+
+        let (_, body) = get("/errors/400?message=%20%20").await;
+        assert_eq!(body["message"], "Bad request");
+    }
+
+    #[tokio::test]
+    async fn version_reports_build_metadata() {
+        let (status, body) = get("/version").await;
+        assert_eq!(status, StatusCode::OK);
+        for field in ["version", "git_commit", "build_timestamp", "rustc_version"] {
+            let value = body["data"][field].as_str().unwrap_or_default();
+            assert!(!value.is_empty(), "missing {field}");
+        }
+        assert_eq!(body["data"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[tokio::test]
+    async fn wrapped_envelope_wraps_sample_routes() {
+        let (status, Json(response)) = version(Locale::En).await;
+        let body = render(&response, ApiEnvelope::Wrapped);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert_eq!(body["message"], "Build information");
+        assert_eq!(body["data"]["version"], env!("CARGO_PKG_VERSION"));
+
+        let (status, Json(response)) = simulate_error(
+            Locale::En,
+            ApiPath(404),
+            ApiQuery(SampleQuery { message: None }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            render(&response, ApiEnvelope::Wrapped),
+            json!({
+                "success": false,
+                "message": "Not found",
+                "code": "not_found",
+                "data": null,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn raw_envelope_returns_bare_data_and_error_object() {
+        let (status, Json(response)) = version(Locale::En).await;
+        let body = render(&response, ApiEnvelope::Raw);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert!(body.get("success").is_none());
+
+        let (status, Json(response)) = simulate_error(
+            Locale::En,
+            ApiPath(404),
+            ApiQuery(SampleQuery { message: None }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            render(&response, ApiEnvelope::Raw),
+            json!({ "error": "Not found", "code": "not_found" })
+        );
     }
 }
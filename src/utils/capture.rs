@@ -0,0 +1,71 @@
+//! Records `tracing` events so tests can assert on their level and fields.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    subscriber::DefaultGuard,
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+#[derive(Clone, Debug)]
+pub struct CapturedEvent {
+    pub level: Level,
+    /// Every recorded field, with the event text under `message`
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+pub struct CapturedEvents(Arc<Mutex<Vec<CapturedEvent>>>);
+
+impl CapturedEvents {
+    /// Captures events emitted on the current thread until the guard is dropped.
+    pub fn install() -> (Self, DefaultGuard) {
+        let events = CapturedEvents::default();
+        let guard = tracing_subscriber::registry()
+            .with(events.clone())
+            .set_default();
+        (events, guard)
+    }
+
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Events whose message is exactly `message`.
+    pub fn with_message(&self, message: &str) -> Vec<CapturedEvent> {
+        self.events()
+            .into_iter()
+            .filter(|event| event.fields.get("message").map(String::as_str) == Some(message))
+            .collect()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for CapturedEvents {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = BTreeMap::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.0.lock().unwrap().push(CapturedEvent {
+            level: *event.metadata().level(),
+            fields,
+        });
+    }
+}
+
+struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
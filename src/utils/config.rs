@@ -0,0 +1,272 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use axum::http::HeaderValue;
+
+/// Shape of the JSON body produced by `ApiResponse`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiEnvelope {
+    /// `{ success, message, data }` (default)
+    Wrapped,
+    /// Bare `data` on success and `{ error }` on failure
+    Raw,
+}
+
+/// Reads `API_ENVELOPE` (`wrapped` | `raw`) once and caches the result.
+pub fn api_envelope() -> ApiEnvelope {
+    static ENVELOPE: OnceLock<ApiEnvelope> = OnceLock::new();
+    *ENVELOPE.get_or_init(|| match std::env::var("API_ENVELOPE").as_deref() {
+        Ok("raw") => ApiEnvelope::Raw,
+        _ => ApiEnvelope::Wrapped,
+    })
+}
+
+/// Reads a boolean flag (`true`/`1`) from the environment, defaulting to `false`.
+fn env_flag(key: &str) -> bool {
+    matches!(std::env::var(key).as_deref(), Ok("true") | Ok("1"))
+}
+
+/// Parses `key` from the environment, or `None` when it is unset or blank.
+/// An unparseable value aborts startup instead of silently becoming the default.
+fn env_parse<T: FromStr>(key: &str) -> Option<T> {
+    let value = std::env::var(key).ok()?;
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(
+        trimmed
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid {key}: {value:?}")),
+    )
+}
+
+/// Whether `X-Forwarded-For`/`Forwarded` headers come from a trusted proxy (`TRUST_PROXY`).
+pub fn trust_proxy() -> bool {
+    static TRUST_PROXY: OnceLock<bool> = OnceLock::new();
+    *TRUST_PROXY.get_or_init(|| env_flag("TRUST_PROXY"))
+}
+
+/// Number of proxies in front of the app (`TRUSTED_PROXY_HOPS`, default 1), i.e. how
+/// many forwarding entries from the right were appended by our own infrastructure.
+/// Only used with `TRUST_PROXY`.
+pub fn trusted_proxy_hops() -> usize {
+    static TRUSTED_PROXY_HOPS: OnceLock<usize> = OnceLock::new();
+    *TRUSTED_PROXY_HOPS.get_or_init(|| match env_parse("TRUSTED_PROXY_HOPS").unwrap_or(1) {
+        0 => panic!("invalid TRUSTED_PROXY_HOPS: must be at least 1"),
+        hops => hops,
+    })
+}
+
+/// Whether diagnostic routes such as `/debug/echo` are mounted (`DEBUG_ROUTES`).
+/// Must stay off in production.
+pub fn debug_routes() -> bool {
+    static DEBUG_ROUTES: OnceLock<bool> = OnceLock::new();
+    *DEBUG_ROUTES.get_or_init(|| env_flag("DEBUG_ROUTES"))
+}
+
+/// Whether counts above JavaScript's safe integer range are serialized as strings
+/// (`JSON_BIGINT_AS_STRING`).
+pub fn bigint_as_string() -> bool {
+    static BIGINT_AS_STRING: OnceLock<bool> = OnceLock::new();
+    *BIGINT_AS_STRING.get_or_init(|| env_flag("JSON_BIGINT_AS_STRING"))
+}
+
+/// Requests slower than this many milliseconds are logged as warnings (`SLOW_REQUEST_MS`).
+pub fn slow_request_ms() -> u128 {
+    static SLOW_REQUEST_MS: OnceLock<u128> = OnceLock::new();
+    *SLOW_REQUEST_MS.get_or_init(|| env_parse("SLOW_REQUEST_MS").unwrap_or(1000))
+}
+
+/// Maintenance state read from `MAINTENANCE_MODE` (`off` | `read-only` | `full`).
+///
+/// The mode is fixed for the life of the process: entering or leaving
+/// maintenance means restarting with a different value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaintenanceMode {
+    Off,
+    /// Only safe methods (GET/HEAD/OPTIONS) are served
+    ReadOnly,
+    /// Every request is rejected
+    Full,
+}
+
+impl FromStr for MaintenanceMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "off" => Ok(MaintenanceMode::Off),
+            "read-only" => Ok(MaintenanceMode::ReadOnly),
+            "full" => Ok(MaintenanceMode::Full),
+            _ => Err(()),
+        }
+    }
+}
+
+pub fn maintenance_mode() -> MaintenanceMode {
+    static MAINTENANCE_MODE: OnceLock<MaintenanceMode> = OnceLock::new();
+    *MAINTENANCE_MODE.get_or_init(|| env_parse("MAINTENANCE_MODE").unwrap_or(MaintenanceMode::Off))
+}
+
+/// Additional JSON keys to mask in logged/echoed bodies (`REDACT_EXTRA_FIELDS`, comma-separated).
+pub fn redact_extra_fields() -> &'static [String] {
+    static REDACT_EXTRA_FIELDS: OnceLock<Vec<String>> = OnceLock::new();
+    REDACT_EXTRA_FIELDS.get_or_init(|| {
+        std::env::var("REDACT_EXTRA_FIELDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|field| field.trim().to_ascii_lowercase())
+            .filter(|field| !field.is_empty())
+            .collect()
+    })
+}
+
+/// `Strict-Transport-Security` settings from `HSTS_MAX_AGE` (seconds, default one
+/// year, `0` disables), `HSTS_INCLUDE_SUBDOMAINS` and `HSTS_PRELOAD`. A malformed
+/// max-age aborts startup rather than falling back to a year-long policy.
+pub struct HstsConfig {
+    pub max_age: u64,
+    pub include_subdomains: bool,
+    pub preload: bool,
+}
+
+pub fn hsts() -> &'static HstsConfig {
+    static HSTS: OnceLock<HstsConfig> = OnceLock::new();
+    HSTS.get_or_init(|| HstsConfig {
+        max_age: env_parse("HSTS_MAX_AGE").unwrap_or(31_536_000),
+        include_subdomains: env_flag("HSTS_INCLUDE_SUBDOMAINS"),
+        preload: env_flag("HSTS_PRELOAD"),
+    })
+}
+
+/// How paths ending in `/` are handled (`TRAILING_SLASH`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// `/users` and `/users/` are distinct routes (default)
+    Strict,
+    /// `/users/` answers with a 308 to `/users`
+    Redirect,
+    /// `/users/` is routed as `/users`
+    Ignore,
+}
+
+impl FromStr for TrailingSlash {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "strict" => Ok(TrailingSlash::Strict),
+            "redirect" => Ok(TrailingSlash::Redirect),
+            "ignore" => Ok(TrailingSlash::Ignore),
+            _ => Err(()),
+        }
+    }
+}
+
+pub fn trailing_slash() -> TrailingSlash {
+    static TRAILING_SLASH: OnceLock<TrailingSlash> = OnceLock::new();
+    *TRAILING_SLASH.get_or_init(|| env_parse("TRAILING_SLASH").unwrap_or(TrailingSlash::Strict))
+}
+
+/// Max in-flight requests per client IP (`MAX_CONCURRENT_REQUESTS_PER_IP`); `0` disables the cap.
+pub fn max_concurrent_requests_per_ip() -> usize {
+    static MAX_CONCURRENT: OnceLock<usize> = OnceLock::new();
+    *MAX_CONCURRENT.get_or_init(|| env_parse("MAX_CONCURRENT_REQUESTS_PER_IP").unwrap_or(0))
+}
+
+/// Deployment environment name (`APP_ENV`), defaulting to `development`.
+pub fn app_env() -> &'static str {
+    static APP_ENV: OnceLock<String> = OnceLock::new();
+    APP_ENV.get_or_init(|| std::env::var("APP_ENV").unwrap_or_else(|_| "development".to_string()))
+}
+
+/// Longest accepted `?search=` term in characters (`SEARCH_MAX_LENGTH`, default 128).
+pub fn search_max_length() -> usize {
+    static SEARCH_MAX_LENGTH: OnceLock<usize> = OnceLock::new();
+    *SEARCH_MAX_LENGTH.get_or_init(|| env_parse("SEARCH_MAX_LENGTH").unwrap_or(128))
+}
+
+/// Whether responses carry a `Server-Timing` header (`SERVER_TIMING`).
+pub fn server_timing() -> bool {
+    static SERVER_TIMING: OnceLock<bool> = OnceLock::new();
+    *SERVER_TIMING.get_or_init(|| env_flag("SERVER_TIMING"))
+}
+
+/// Which origins may make cross-origin requests.
+pub enum AllowedOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+/// CORS settings read from `CORS_ALLOWED_ORIGINS` (comma-separated or `*`),
+/// `CORS_ALLOW_CREDENTIALS` and `CORS_MAX_AGE_SECONDS`.
+pub struct CorsConfig {
+    pub allowed_origins: AllowedOrigins,
+    pub allow_credentials: bool,
+    pub max_age_seconds: Option<u64>,
+}
+
+impl CorsConfig {
+    pub fn parse(
+        origins: &str,
+        allow_credentials: bool,
+        max_age_seconds: Option<u64>,
+    ) -> Result<Self, String> {
+        let allowed_origins = if origins.trim() == "*" {
+            AllowedOrigins::Any
+        } else {
+            let list = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(|origin| {
+                    HeaderValue::from_str(origin)
+                        .map_err(|_| format!("invalid origin in CORS_ALLOWED_ORIGINS: {origin}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowedOrigins::List(list)
+        };
+
+        // Browsers refuse credentialed responses with a wildcard origin
+        if allow_credentials && matches!(allowed_origins, AllowedOrigins::Any) {
+            return Err(
+                "CORS_ALLOW_CREDENTIALS=true cannot be combined with CORS_ALLOWED_ORIGINS=*"
+                    .to_string(),
+            );
+        }
+
+        Ok(CorsConfig {
+            allowed_origins,
+            allow_credentials,
+            max_age_seconds,
+        })
+    }
+}
+
+/// The CORS settings; an invalid combination aborts startup.
+pub fn cors() -> &'static CorsConfig {
+    static CORS: OnceLock<CorsConfig> = OnceLock::new();
+    CORS.get_or_init(|| {
+        CorsConfig::parse(
+            &std::env::var("CORS_ALLOWED_ORIGINS").unwrap_or_default(),
+            env_flag("CORS_ALLOW_CREDENTIALS"),
+            env_parse("CORS_MAX_AGE_SECONDS"),
+        )
+        .unwrap_or_else(|error| panic!("invalid CORS configuration: {error}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maintenance_mode_accepts_only_known_values() {
+        assert_eq!("off".parse(), Ok(MaintenanceMode::Off));
+        assert_eq!("read-only".parse(), Ok(MaintenanceMode::ReadOnly));
+        assert_eq!("full".parse(), Ok(MaintenanceMode::Full));
+        for invalid in ["on", "readonly", "FULL", "1"] {
+            assert!(invalid.parse::<MaintenanceMode>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! `Path`, `Query` and `Json` extractors whose rejections use the `ErrorCode`
+//! envelope instead of axum's plain-text bodies.
+
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, FromRequestParts, Path, Query, Request},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::{
+    utils::i18n::Locale,
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+type Rejection = (StatusCode, Json<ApiResponse>);
+
+// axum's explanation goes in `data.reason` so clients can still see what failed
+fn reject(error: ErrorCode, locale: Locale, reason: String) -> Rejection {
+    ApiResponse::error_with_details(error, locale, Some(json!({ "reason": reason })))
+}
+
+/// [`Path`] with an `invalid_path` rejection.
+pub struct ApiPath<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Ok(locale) = Locale::from_request_parts(parts, state).await;
+        match Path::<T>::from_request_parts(parts, state).await {
+            Ok(Path(value)) => Ok(ApiPath(value)),
+            // A route/extractor mismatch is our bug, not the client's
+            Err(rejection) if rejection.status().is_server_error() => Err(reject(
+                ErrorCode::InternalServerError,
+                locale,
+                rejection.body_text(),
+            )),
+            Err(rejection) => Err(reject(
+                ErrorCode::InvalidPath,
+                locale,
+                rejection.body_text(),
+            )),
+        }
+    }
+}
+
+/// [`Query`] with an `invalid_query` rejection.
+pub struct ApiQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ApiQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Ok(locale) = Locale::from_request_parts(parts, state).await;
+        Query::<T>::from_request_parts(parts, state)
+            .await
+            .map(|Query(value)| ApiQuery(value))
+            .map_err(|rejection| reject(ErrorCode::InvalidQuery, locale, rejection.body_text()))
+    }
+}
+
+/// [`Json`] request body whose rejection distinguishes a missing content type,
+/// malformed JSON, a shape mismatch and an oversized body.
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Rejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let (mut parts, body) = request.into_parts();
+        let Ok(locale) = Locale::from_request_parts(&mut parts, state).await;
+        let request = Request::from_parts(parts, body);
+
+        match Json::<T>::from_request(request, state).await {
+            Ok(Json(value)) => Ok(ApiJson(value)),
+            Err(rejection) => {
+                let error = match &rejection {
+                    JsonRejection::MissingJsonContentType(_) => ErrorCode::UnsupportedMediaType,
+                    JsonRejection::JsonSyntaxError(_) => ErrorCode::MalformedJson,
+                    JsonRejection::JsonDataError(_) => ErrorCode::InvalidJsonBody,
+                    _ if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                        ErrorCode::PayloadTooLarge
+                    }
+                    _ => ErrorCode::BadRequest,
+                };
+                Err(reject(error, locale, rejection.body_text()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        extract::DefaultBodyLimit,
+        http::header::CONTENT_TYPE,
+        routing::{get, post},
+        Router,
+    };
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/users/:id", get(|ApiPath(_): ApiPath<u32>| async {}))
+            .route(
+                "/filter",
+                get(|ApiQuery(_): ApiQuery<HashMap<String, u32>>| async {}),
+            )
+            .route(
+                "/users",
+                post(|ApiJson(_): ApiJson<HashMap<String, String>>| async {}),
+            )
+            .layer(DefaultBodyLimit::max(64))
+    }
+
+    async fn send(request: Request) -> (StatusCode, Value) {
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn json_request(content_type: Option<&str>, body: &str) -> Request {
+        let mut request = Request::post("/users");
+        if let Some(content_type) = content_type {
+            request = request.header(CONTENT_TYPE, content_type);
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    fn assert_envelope(body: &Value, code: &str) {
+        assert_eq!(body["success"], false);
+        assert_eq!(body["code"], code);
+        assert!(body["data"]["reason"].is_string());
+    }
+
+    #[tokio::test]
+    async fn invalid_path_parameter_uses_the_envelope() {
+        let (status, body) = send(Request::get("/users/abc").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_envelope(&body, "invalid_path");
+    }
+
+    #[tokio::test]
+    async fn invalid_query_uses_the_envelope() {
+        let (status, body) = send(
+            Request::get("/filter?limit=many")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_envelope(&body, "invalid_query");
+    }
+
+    #[tokio::test]
+    async fn json_rejections_use_the_envelope() {
+        let cases = [
+            (
+                None,
+                "{}",
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported_media_type",
+            ),
+            (
+                Some("application/json"),
+                "{",
+                StatusCode::BAD_REQUEST,
+                "malformed_json",
+            ),
+            (
+                Some("application/json"),
+                r#"{"name": 1}"#,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_body",
+            ),
+            (
+                Some("application/json"),
+                &format!(r#"{{"name": "{}"}}"#, "x".repeat(100)),
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+            ),
+        ];
+        for (content_type, payload, expected_status, code) in cases {
+            let (status, body) = send(json_request(content_type, payload)).await;
+            assert_eq!(status, expected_status, "{code}");
+            assert_envelope(&body, code);
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap, HeaderName, StatusCode},
+    Json,
+};
+
+use serde_json::Value;
+
+use crate::{
+    utils::{config, i18n::Locale},
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+/// JSON keys whose values are always masked by [`redact_sensitive`].
+const SENSITIVE_FIELDS: &[&str] = &[
+    "password",
+    "new_password",
+    "confirm_password",
+    "password_confirmation",
+    "otp",
+    "token",
+];
+
+/// Request headers carrying credentials, masked wherever headers are echoed or logged.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+];
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Whether a header's value must be masked before it is echoed or logged.
+pub fn is_sensitive_header(name: &HeaderName) -> bool {
+    SENSITIVE_HEADERS.contains(&name.as_str())
+}
+
+/// Returns a copy of `value` with sensitive fields masked at any depth, so bodies
+/// can be logged or echoed safely. Extra keys come from `REDACT_EXTRA_FIELDS`.
+pub fn redact_sensitive(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let key_lower = key.to_ascii_lowercase();
+                    let sensitive = SENSITIVE_FIELDS.contains(&key_lower.as_str())
+                        || config::redact_extra_fields().contains(&key_lower);
+                    let value = if sensitive {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact_sensitive(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_sensitive).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Resolves the real client IP for a request.
+///
+/// `trusted_hops` is the number of our own proxies in front of the app; `0` means
+/// there are none, so forwarding headers are client-controlled and ignored in
+/// favour of the socket peer address. Each proxy appends the address it saw, so
+/// the client is the entry `trusted_hops` places from the right; anything further
+/// left was supplied by the client and can be spoofed.
+pub fn client_ip(
+    headers: &HeaderMap,
+    extensions: &Extensions,
+    trusted_hops: usize,
+) -> Option<IpAddr> {
+    if trusted_hops > 0 {
+        let forwarded_for: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        let forwarded: Vec<&str> = headers
+            .get_all("forwarded")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+
+        // An entry that still isn't an address (e.g. "unknown") falls back to the peer
+        let from_proxy = match trusted_entry(&forwarded_for, trusted_hops) {
+            Some(entry) => parse_node(entry),
+            None => trusted_entry(&forwarded, trusted_hops).and_then(parse_forwarded_for),
+        };
+        if from_proxy.is_some() {
+            return from_proxy;
+        }
+    }
+
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+/// The entry `trusted_hops` places from the right. With fewer entries than hops,
+/// every entry was appended by our proxies, so the leftmost one is the client.
+fn trusted_entry<'a>(entries: &[&'a str], trusted_hops: usize) -> Option<&'a str> {
+    entries
+        .iter()
+        .rev()
+        .nth(trusted_hops - 1)
+        .or_else(|| entries.first())
+        .copied()
+}
+
+/// Extracts the address from the `for=` pair of a single RFC 7239 `Forwarded` element.
+fn parse_forwarded_for(element: &str) -> Option<IpAddr> {
+    let value = element.split(';').find_map(|pair| {
+        let (key, value) = pair.trim().split_once('=')?;
+        key.eq_ignore_ascii_case("for").then_some(value)
+    })?;
+    parse_node(value.trim_matches('"'))
+}
+
+/// Parses a forwarding node that may carry a port, as some proxies append
+/// "203.0.113.7:5555" or "[2001:db8::1]:4711" rather than a bare address.
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    if let Some(rest) = value.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    value
+        .parse()
+        .ok()
+        .or_else(|| value.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// Whether the client reached us over HTTPS.
+///
+/// This server only speaks plain HTTP, so HTTPS means TLS was terminated by a
+/// proxy; its `X-Forwarded-Proto`/`Forwarded: proto=` is only believed when
+/// `trust_proxy` is set.
+pub fn is_https(headers: &HeaderMap, trust_proxy: bool) -> bool {
+    if !trust_proxy {
+        return false;
+    }
+
+    let forwarded_proto = headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next_back())
+        .map(str::trim);
+    if let Some(proto) = forwarded_proto {
+        return proto.eq_ignore_ascii_case("https");
+    }
+
+    headers
+        .get_all("forwarded")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .next_back()
+        .and_then(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("proto")
+                    .then_some(value.trim_matches('"'))
+            })
+        })
+        .is_some_and(|proto| proto.eq_ignore_ascii_case("https"))
+}
+
+/// Extractor yielding the client IP resolved by [`client_ip`] using `TRUST_PROXY`
+/// and `TRUSTED_PROXY_HOPS`.
+pub struct ClientIp(pub IpAddr);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = (StatusCode, Json<ApiResponse>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Ok(locale) = Locale::from_request_parts(parts, state).await;
+        let trusted_hops = if config::trust_proxy() {
+            config::trusted_proxy_hops()
+        } else {
+            0
+        };
+        client_ip(&parts.headers, &parts.extensions, trusted_hops)
+            .map(ClientIp)
+            .ok_or_else(|| ApiResponse::error(ErrorCode::ClientAddressUnavailable, locale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    const PEER: &str = "10.0.0.9:5000";
+
+    fn peer_extensions() -> Extensions {
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(PEER.parse::<SocketAddr>().unwrap()));
+        extensions
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn ip(value: &str) -> Option<IpAddr> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn redacts_login_body_at_any_depth_and_key_case() {
+        let body = serde_json::json!({
+            "email": "ada@example.com",
+            "Password": "hunter2",
+            "profile": { "name": "Ada", "NEW_PASSWORD": "hunter3", "otp": "123456" },
+            "sessions": [
+                { "device": "laptop", "token": "abc" },
+                { "device": "phone", "Token": "def" }
+            ],
+        });
+
+        assert_eq!(
+            redact_sensitive(&body),
+            serde_json::json!({
+                "email": "ada@example.com",
+                "Password": REDACTED,
+                "profile": { "name": "Ada", "NEW_PASSWORD": REDACTED, "otp": REDACTED },
+                "sessions": [
+                    { "device": "laptop", "token": REDACTED },
+                    { "device": "phone", "Token": REDACTED }
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn non_object_values_pass_through() {
+        let body = serde_json::json!(["password", 1, null]);
+        assert_eq!(redact_sensitive(&body), body);
+    }
+
+    #[test]
+    fn spoofed_forwarding_headers_are_ignored_without_proxy_trust() {
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4"), ("forwarded", "for=5.6.7.8")]);
+        assert_eq!(client_ip(&headers, &peer_extensions(), 0), ip("10.0.0.9"));
+    }
+
+    #[test]
+    fn client_prepended_entries_are_not_trusted() {
+        // The client sent "1.2.3.4"; our single proxy appended the real address
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7")]);
+        assert_eq!(
+            client_ip(&headers, &peer_extensions(), 1),
+            ip("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn multi_hop_forwarded_for_skips_trusted_proxies() {
+        // client -> CDN -> load balancer -> app; the LB appended the CDN's address
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7, 198.51.100.2")]);
+        assert_eq!(
+            client_ip(&headers, &peer_extensions(), 2),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            client_ip(&headers, &peer_extensions(), 1),
+            ip("198.51.100.2")
+        );
+    }
+
+    #[test]
+    fn forwarded_for_entries_with_ports_are_parsed() {
+        for (entry, expected) in [
+            ("203.0.113.7:5555", "203.0.113.7"),
+            ("[2001:db8::1]:4711", "2001:db8::1"),
+            ("[2001:db8::1]", "2001:db8::1"),
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-forwarded-for", HeaderValue::from_str(entry).unwrap());
+            assert_eq!(client_ip(&headers, &peer_extensions(), 1), ip(expected));
+        }
+    }
+
+    #[test]
+    fn unparseable_trusted_entry_falls_back_to_peer_address() {
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4, unknown")]);
+        assert_eq!(client_ip(&headers, &peer_extensions(), 1), ip("10.0.0.9"));
+    }
+
+    #[test]
+    fn repeated_forwarded_for_headers_are_read_as_one_list() {
+        let headers = headers(&[
+            ("x-forwarded-for", "1.2.3.4, 203.0.113.7"),
+            ("x-forwarded-for", "198.51.100.2"),
+        ]);
+        assert_eq!(
+            client_ip(&headers, &peer_extensions(), 2),
+            ip("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn fewer_entries_than_hops_uses_the_leftmost() {
+        let headers = headers(&[("x-forwarded-for", "203.0.113.7")]);
+        assert_eq!(
+            client_ip(&headers, &peer_extensions(), 2),
+            ip("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn rfc7239_forwarded_is_used_without_x_forwarded_for() {
+        let headers = headers(&[(
+            "forwarded",
+            "for=1.2.3.4, for=\"[2001:db8::1]:4711\";proto=https, for=198.51.100.2",
+        )]);
+        assert_eq!(
+            client_ip(&headers, &peer_extensions(), 2),
+            ip("2001:db8::1")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_peer_address_without_forwarding_headers() {
+        assert_eq!(
+            client_ip(&HeaderMap::new(), &peer_extensions(), 1),
+            ip("10.0.0.9")
+        );
+    }
+
+    #[test]
+    fn https_is_detected_from_forwarded_proto_only_when_trusted() {
+        let forwarded = headers(&[("x-forwarded-proto", "https")]);
+        assert!(is_https(&forwarded, true));
+        assert!(!is_https(&forwarded, false));
+        assert!(!is_https(&headers(&[("x-forwarded-proto", "http")]), true));
+        assert!(!is_https(&HeaderMap::new(), true));
+    }
+
+    #[test]
+    fn last_forwarded_proto_wins() {
+        // Our proxy appends what it saw; a client-supplied "https" comes first
+        assert!(!is_https(
+            &headers(&[("x-forwarded-proto", "https, http")]),
+            true
+        ));
+        assert!(is_https(
+            &headers(&[("x-forwarded-proto", "http, HTTPS")]),
+            true
+        ));
+    }
+
+    #[test]
+    fn https_is_detected_from_rfc7239_forwarded() {
+        let forwarded = headers(&[(
+            "forwarded",
+            "for=1.2.3.4;proto=http, for=5.6.7.8;proto=https",
+        )]);
+        assert!(is_https(&forwarded, true));
+        assert!(!is_https(
+            &headers(&[("forwarded", "for=1.2.3.4;proto=http")]),
+            true
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use std::{collections::HashMap, convert::Infallible, sync::OnceLock};
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::ACCEPT_LANGUAGE, request::Parts},
+};
+
+/// Locales with a message catalog. English messages are the lookup keys,
+/// so `En` needs no catalog and is the fallback for any missing entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim();
+        if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else if primary.eq_ignore_ascii_case("fr") {
+            Some(Locale::Fr)
+        } else {
+            None
+        }
+    }
+
+    /// Picks the highest-weighted supported locale from an `Accept-Language` value.
+    pub fn negotiate(accept_language: &str) -> Self {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.trim().split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+                Some((tag, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+        // Stable sort keeps header order for equal weights
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| Locale::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    /// Translates an English message, falling back to it when no entry exists.
+    pub fn translate(self, message: &'static str) -> &'static str {
+        let catalog = match self {
+            Locale::En => return message,
+            Locale::Fr => fr_catalog(),
+        };
+        catalog.get(message).copied().unwrap_or(message)
+    }
+}
+
+fn fr_catalog() -> &'static HashMap<&'static str, &'static str> {
+    static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        serde_json::from_str(include_str!("../../locales/fr.json"))
+            .expect("locales/fr.json must be a flat JSON object of strings")
+    })
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::negotiate)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_french_from_region_tag() {
+        assert_eq!(Locale::negotiate("fr-CA"), Locale::Fr);
+        assert_eq!(Locale::negotiate("fr_FR, en"), Locale::Fr);
+    }
+
+    #[test]
+    fn highest_quality_wins_regardless_of_order() {
+        assert_eq!(Locale::negotiate("en;q=0.5, fr;q=0.9"), Locale::Fr);
+        assert_eq!(Locale::negotiate("fr;q=0.4, en;q=0.8"), Locale::En);
+    }
+
+    #[test]
+    fn unsupported_and_refused_ranges_are_skipped() {
+        assert_eq!(Locale::negotiate("de, fr;q=0.1"), Locale::Fr);
+        assert_eq!(Locale::negotiate("fr;q=0, de"), Locale::En);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn translates_with_english_fallback() {
+        assert_eq!(Locale::Fr.translate("Not found"), "Introuvable");
+        assert_eq!(Locale::Fr.translate("No such message"), "No such message");
+        assert_eq!(Locale::En.translate("Not found"), "Not found");
+    }
+}
@@ -1,2 +1,8 @@
+#[cfg(test)]
+pub mod capture;
+pub mod config;
 pub mod constants;
+pub mod extract;
 pub mod helpers;
+pub mod i18n;
+pub mod pagination;
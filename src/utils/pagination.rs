@@ -0,0 +1,385 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{
+    utils::{config, i18n::Locale},
+    views::{error_code::ErrorCode, response::ApiResponse},
+};
+
+pub const DEFAULT_PER_PAGE: u64 = 20;
+pub const MAX_PER_PAGE: u64 = 100;
+
+/// Implemented by resources that can be listed through [`PageRequest`].
+pub trait Paginated {
+    /// Fields accepted by `?sort=`; prefix with `-` for descending order.
+    const SORTABLE_FIELDS: &'static [&'static str];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Validated `page`/`per_page`/`sort`/`search` query parameters for listing `T`.
+///
+/// `DEFAULT` is the page size used when `per_page` is omitted, so each endpoint
+/// can pick its own (e.g. `PageRequest<User, 10>`); `MAX_PER_PAGE` still applies.
+pub struct PageRequest<T, const DEFAULT: u64 = DEFAULT_PER_PAGE> {
+    pub page: u64,
+    pub per_page: u64,
+    pub sort: Option<(&'static str, SortDirection)>,
+    pub search: Option<String>,
+    resource: PhantomData<fn() -> T>,
+}
+
+impl<T, const DEFAULT: u64> PageRequest<T, DEFAULT> {
+    /// Number of records to skip for the requested page.
+    pub fn offset(&self) -> u64 {
+        (self.page - 1).saturating_mul(self.per_page)
+    }
+
+    /// `search` as a substring `LIKE` pattern (`ESCAPE '\'`). Wildcards in the term
+    /// are escaped, so `%` or `_` match themselves instead of widening the scan.
+    pub fn search_pattern(&self) -> Option<String> {
+        let search = self.search.as_ref()?;
+        let mut pattern = String::with_capacity(search.len() + 2);
+        pattern.push('%');
+        for c in search.chars() {
+            if matches!(c, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push('%');
+        Some(pattern)
+    }
+
+    /// Pagination metadata for a listing with `total` matching records.
+    pub fn info(&self, total: u64) -> PaginationInfo {
+        PaginationInfo {
+            page: self.page,
+            per_page: self.per_page,
+            total,
+            total_pages: total_pages(total, self.per_page),
+        }
+    }
+}
+
+/// Largest integer a JavaScript number represents exactly (2^53 - 1).
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+#[derive(Serialize)]
+pub struct PaginationInfo {
+    pub page: u64,
+    pub per_page: u64,
+    #[serde(serialize_with = "serialize_count")]
+    pub total: u64,
+    #[serde(serialize_with = "serialize_count")]
+    pub total_pages: u64,
+}
+
+/// Integer ceiling division, so large totals never lose precision through `f64`.
+pub fn total_pages(total: u64, per_page: u64) -> u64 {
+    if per_page == 0 {
+        return 0;
+    }
+    total.div_ceil(per_page)
+}
+
+// Emits counts beyond JS-safe range as strings when JSON_BIGINT_AS_STRING is set
+fn serialize_count<S: Serializer>(count: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_count_as(*count, config::bigint_as_string(), serializer)
+}
+
+fn serialize_count_as<S: Serializer>(
+    count: u64,
+    bigint_as_string: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if count > MAX_SAFE_INTEGER && bigint_as_string {
+        serializer.collect_str(&count)
+    } else {
+        serializer.serialize_u64(count)
+    }
+}
+
+// Raw strings so each field can report its own error instead of a generic query rejection
+#[derive(Deserialize)]
+struct RawPageQuery {
+    page: Option<String>,
+    per_page: Option<String>,
+    sort: Option<String>,
+    search: Option<String>,
+}
+
+#[async_trait]
+impl<T: Paginated, S: Send + Sync, const DEFAULT: u64> FromRequestParts<S>
+    for PageRequest<T, DEFAULT>
+{
+    type Rejection = (StatusCode, Json<ApiResponse>);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Ok(locale) = Locale::from_request_parts(parts, state).await;
+        let Query(raw) = Query::<RawPageQuery>::from_request_parts(parts, state)
+            .await
+            .map_err(|_| ApiResponse::error(ErrorCode::InvalidQuery, locale))?;
+        let mut errors = BTreeMap::new();
+
+        let page = match raw.page.as_deref().map(str::trim) {
+            None | Some("") => 1,
+            Some(value) => match value.parse::<u64>() {
+                Ok(page) if page >= 1 => page,
+                _ => {
+                    errors.insert("page", "must be a positive integer".to_string());
+                    1
+                }
+            },
+        };
+
+        let per_page = match raw.per_page.as_deref().map(str::trim) {
+            None | Some("") => DEFAULT.clamp(1, MAX_PER_PAGE),
+            Some(value) => match value.parse::<u64>() {
+                Ok(per_page) if per_page >= 1 => per_page.min(MAX_PER_PAGE),
+                _ => {
+                    errors.insert("per_page", "must be a positive integer".to_string());
+                    DEFAULT
+                }
+            },
+        };
+
+        let sort = match raw.sort.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(value) => {
+                let (field, direction) = match value.strip_prefix('-') {
+                    Some(field) => (field, SortDirection::Desc),
+                    None => (value, SortDirection::Asc),
+                };
+                match T::SORTABLE_FIELDS.iter().find(|allowed| **allowed == field) {
+                    Some(allowed) => Some((*allowed, direction)),
+                    None => {
+                        errors.insert(
+                            "sort",
+                            format!("must be one of: {}", T::SORTABLE_FIELDS.join(", ")),
+                        );
+                        None
+                    }
+                }
+            }
+        };
+
+        // Length is checked on the raw value so padding can't slip past the cap
+        let max_length = config::search_max_length();
+        if raw
+            .search
+            .as_ref()
+            .is_some_and(|search| search.chars().count() > max_length)
+        {
+            errors.insert("search", format!("must be at most {max_length} characters"));
+        }
+        let search = raw
+            .search
+            .map(|search| search.trim().to_string())
+            .filter(|search| !search.is_empty());
+
+        if !errors.is_empty() {
+            return Err(ApiResponse::error_with_details(
+                ErrorCode::InvalidPagination,
+                locale,
+                Some(errors),
+            ));
+        }
+
+        Ok(PageRequest {
+            page,
+            per_page,
+            sort,
+            search,
+            resource: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    struct Item;
+
+    impl Paginated for Item {
+        const SORTABLE_FIELDS: &'static [&'static str] = &["id", "name"];
+    }
+
+    async fn extract<const DEFAULT: u64>(
+        query: &str,
+    ) -> Result<PageRequest<Item, DEFAULT>, (StatusCode, Json<ApiResponse>)> {
+        let (mut parts, ()) = Request::get(format!("/items?{query}"))
+            .body(())
+            .unwrap()
+            .into_parts();
+        PageRequest::<Item, DEFAULT>::from_request_parts(&mut parts, &()).await
+    }
+
+    /// Asserts a 422 `invalid_pagination` rejection naming exactly `fields`.
+    async fn assert_rejected(query: &str, fields: &[&str]) {
+        let Err((status, Json(response))) = extract::<DEFAULT_PER_PAGE>(query).await else {
+            panic!("{query:?} should be rejected");
+        };
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.code, Some("invalid_pagination"));
+        let details = response.data.unwrap();
+        let mut rejected: Vec<&str> = details
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        rejected.sort_unstable();
+        assert_eq!(rejected, fields, "{query:?}");
+    }
+
+    #[tokio::test]
+    async fn defaults_apply_when_parameters_are_omitted() {
+        let page = extract::<DEFAULT_PER_PAGE>("").await.ok().unwrap();
+        assert_eq!((page.page, page.per_page), (1, DEFAULT_PER_PAGE));
+        assert!(page.sort.is_none());
+        assert!(page.search.is_none());
+        assert_eq!(page.offset(), 0);
+    }
+
+    #[tokio::test]
+    async fn endpoint_default_applies_when_per_page_is_omitted() {
+        let page = extract::<5>("page=2").await.ok().unwrap();
+        assert_eq!(page.per_page, 5);
+        assert_eq!(page.offset(), 5);
+
+        let page = extract::<5>("per_page=7").await.ok().unwrap();
+        assert_eq!(page.per_page, 7);
+
+        // The global cap still wins over an oversized default
+        let page = extract::<500>("").await.ok().unwrap();
+        assert_eq!(page.per_page, MAX_PER_PAGE);
+    }
+
+    #[tokio::test]
+    async fn valid_parameters_are_normalized() {
+        let page = extract::<DEFAULT_PER_PAGE>("page=3&per_page=500&sort=-name&search=%20ada%20")
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!((page.page, page.per_page), (3, MAX_PER_PAGE));
+        assert_eq!(page.sort, Some(("name", SortDirection::Desc)));
+        assert_eq!(page.search.as_deref(), Some("ada"));
+        assert_eq!(page.offset(), 2 * MAX_PER_PAGE);
+    }
+
+    #[tokio::test]
+    async fn invalid_page_is_rejected() {
+        for query in ["page=0", "page=-1", "page=abc"] {
+            assert_rejected(query, &["page"]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_per_page_is_rejected() {
+        for query in ["per_page=0", "per_page=ten", "per_page=1.5"] {
+            assert_rejected(query, &["per_page"]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_sort_field_is_rejected() {
+        for query in ["sort=password", "sort=-email", "sort=--id"] {
+            assert_rejected(query, &["sort"]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn oversized_search_is_rejected() {
+        let query = format!("search={}", "a".repeat(config::search_max_length() + 1));
+        assert_rejected(&query, &["search"]).await;
+    }
+
+    #[tokio::test]
+    async fn oversized_search_is_rejected_before_trimming() {
+        let max_length = config::search_max_length();
+        for term in [
+            "%".repeat(max_length + 1),
+            format!("{}x", " ".repeat(max_length)),
+        ] {
+            let query = format!("search={}", term.replace('%', "%25").replace(' ', "%20"));
+            assert_rejected(&query, &["search"]).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn wildcards_in_search_are_escaped_not_stripped() {
+        let page = extract::<DEFAULT_PER_PAGE>("search=first_last")
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!(page.search.as_deref(), Some("first_last"));
+        assert_eq!(page.search_pattern().as_deref(), Some("%first\\_last%"));
+
+        let page = extract::<DEFAULT_PER_PAGE>("search=%25%25%5C")
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!(page.search_pattern().as_deref(), Some("%\\%\\%\\\\%"));
+    }
+
+    #[tokio::test]
+    async fn every_invalid_field_is_reported_at_once() {
+        assert_rejected("page=0&per_page=x&sort=nope", &["page", "per_page", "sort"]).await;
+    }
+
+    #[test]
+    fn total_pages_rounds_up_with_integer_math() {
+        assert_eq!(total_pages(0, 20), 0);
+        assert_eq!(total_pages(1, 20), 1);
+        assert_eq!(total_pages(20, 20), 1);
+        assert_eq!(total_pages(21, 20), 2);
+        assert_eq!(total_pages(u64::MAX, 1), u64::MAX);
+        assert_eq!(total_pages(u64::MAX, 2), u64::MAX / 2 + 1);
+        // 2^53 + 1 is not representable as f64, so a float ceil would yield 2^52
+        assert_eq!(total_pages((1 << 53) + 1, 2), (1 << 52) + 1);
+    }
+
+    #[test]
+    fn total_pages_is_zero_when_per_page_is_zero() {
+        assert_eq!(total_pages(0, 0), 0);
+        assert_eq!(total_pages(42, 0), 0);
+    }
+
+    fn count_json(count: u64, bigint_as_string: bool) -> serde_json::Value {
+        serialize_count_as(count, bigint_as_string, serde_json::value::Serializer).unwrap()
+    }
+
+    #[test]
+    fn unsafe_counts_become_strings_only_when_enabled() {
+        let unsafe_count = MAX_SAFE_INTEGER + 1;
+        assert_eq!(
+            count_json(unsafe_count, true),
+            serde_json::json!(unsafe_count.to_string())
+        );
+        assert_eq!(
+            count_json(unsafe_count, false),
+            serde_json::json!(unsafe_count)
+        );
+    }
+
+    #[test]
+    fn safe_counts_stay_numbers() {
+        for count in [0, 42, MAX_SAFE_INTEGER] {
+            assert_eq!(count_json(count, true), serde_json::json!(count));
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use axum::http::StatusCode;
+
+/// Canonical error conditions. Each maps to exactly one status, message and
+/// machine-readable code, so the same condition looks identical on every endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    RouteNotFound,
+    MethodNotAllowed,
+    UnprocessableEntity,
+    InvalidPath,
+    InvalidQuery,
+    UnsupportedMediaType,
+    MalformedJson,
+    InvalidJsonBody,
+    PayloadTooLarge,
+    InvalidPagination,
+    TooManyConcurrentRequests,
+    InternalServerError,
+    ClientAddressUnavailable,
+    Unknown,
+    NotImplemented,
+    ServiceUnavailable,
+    Maintenance,
+}
+
+impl ErrorCode {
+    /// `(status, English message, code)` for this condition.
+    pub fn parts(self) -> (StatusCode, &'static str, &'static str) {
+        match self {
+            ErrorCode::BadRequest => (StatusCode::BAD_REQUEST, "Bad request", "bad_request"),
+            ErrorCode::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized", "unauthorized"),
+            ErrorCode::Forbidden => (StatusCode::FORBIDDEN, "Forbidden", "forbidden"),
+            ErrorCode::NotFound => (StatusCode::NOT_FOUND, "Not found", "not_found"),
+            ErrorCode::RouteNotFound => {
+                (StatusCode::NOT_FOUND, "Route not found", "route_not_found")
+            }
+            ErrorCode::MethodNotAllowed => (
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Method not allowed",
+                "method_not_allowed",
+            ),
+            ErrorCode::UnprocessableEntity => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Unprocessable entity",
+                "unprocessable_entity",
+            ),
+            ErrorCode::InvalidPath => (
+                StatusCode::BAD_REQUEST,
+                "Invalid path parameter",
+                "invalid_path",
+            ),
+            ErrorCode::InvalidQuery => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Invalid query string",
+                "invalid_query",
+            ),
+            ErrorCode::UnsupportedMediaType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Expected a JSON request body",
+                "unsupported_media_type",
+            ),
+            ErrorCode::MalformedJson => (
+                StatusCode::BAD_REQUEST,
+                "Malformed JSON body",
+                "malformed_json",
+            ),
+            ErrorCode::InvalidJsonBody => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Request body has invalid fields",
+                "invalid_body",
+            ),
+            ErrorCode::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "Request body is too large",
+                "payload_too_large",
+            ),
+            ErrorCode::InvalidPagination => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Invalid pagination parameters",
+                "invalid_pagination",
+            ),
+            ErrorCode::TooManyConcurrentRequests => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many concurrent requests",
+                "too_many_concurrent_requests",
+            ),
+            ErrorCode::InternalServerError => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal server error",
+                "internal_error",
+            ),
+            ErrorCode::ClientAddressUnavailable => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Unable to determine client address",
+                "client_address_unavailable",
+            ),
+            ErrorCode::Unknown => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Unknown error",
+                "unknown_error",
+            ),
+            ErrorCode::NotImplemented => (
+                StatusCode::NOT_IMPLEMENTED,
+                "Not implemented",
+                "not_implemented",
+            ),
+            ErrorCode::ServiceUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Service unavailable",
+                "service_unavailable",
+            ),
+            ErrorCode::Maintenance => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Service is under maintenance, please retry later",
+                "maintenance",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::i18n::Locale;
+    use std::collections::HashSet;
+
+    /// Every condition with its pinned status and code. Changing a row is a
+    /// breaking API change for clients that branch on them.
+    const CANONICAL: &[(ErrorCode, StatusCode, &str)] = &[
+        (
+            ErrorCode::BadRequest,
+            StatusCode::BAD_REQUEST,
+            "bad_request",
+        ),
+        (
+            ErrorCode::Unauthorized,
+            StatusCode::UNAUTHORIZED,
+            "unauthorized",
+        ),
+        (ErrorCode::Forbidden, StatusCode::FORBIDDEN, "forbidden"),
+        (ErrorCode::NotFound, StatusCode::NOT_FOUND, "not_found"),
+        (
+            ErrorCode::RouteNotFound,
+            StatusCode::NOT_FOUND,
+            "route_not_found",
+        ),
+        (
+            ErrorCode::MethodNotAllowed,
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+        ),
+        (
+            ErrorCode::UnprocessableEntity,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "unprocessable_entity",
+        ),
+        (
+            ErrorCode::InvalidPath,
+            StatusCode::BAD_REQUEST,
+            "invalid_path",
+        ),
+        (
+            ErrorCode::InvalidQuery,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_query",
+        ),
+        (
+            ErrorCode::UnsupportedMediaType,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+        ),
+        (
+            ErrorCode::MalformedJson,
+            StatusCode::BAD_REQUEST,
+            "malformed_json",
+        ),
+        (
+            ErrorCode::InvalidJsonBody,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_body",
+        ),
+        (
+            ErrorCode::PayloadTooLarge,
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "payload_too_large",
+        ),
+        (
+            ErrorCode::InvalidPagination,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "invalid_pagination",
+        ),
+        (
+            ErrorCode::TooManyConcurrentRequests,
+            StatusCode::TOO_MANY_REQUESTS,
+            "too_many_concurrent_requests",
+        ),
+        (
+            ErrorCode::InternalServerError,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+        ),
+        (
+            ErrorCode::ClientAddressUnavailable,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "client_address_unavailable",
+        ),
+        (
+            ErrorCode::Unknown,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "unknown_error",
+        ),
+        (
+            ErrorCode::NotImplemented,
+            StatusCode::NOT_IMPLEMENTED,
+            "not_implemented",
+        ),
+        (
+            ErrorCode::ServiceUnavailable,
+            StatusCode::SERVICE_UNAVAILABLE,
+            "service_unavailable",
+        ),
+        (
+            ErrorCode::Maintenance,
+            StatusCode::SERVICE_UNAVAILABLE,
+            "maintenance",
+        ),
+    ];
+
+    #[test]
+    fn each_condition_has_its_canonical_status_and_code() {
+        for &(error, status, code) in CANONICAL {
+            let (actual_status, message, actual_code) = error.parts();
+            assert_eq!(actual_status, status, "{error:?}");
+            assert_eq!(actual_code, code, "{error:?}");
+            assert!(!message.is_empty(), "{error:?}");
+        }
+    }
+
+    #[test]
+    fn codes_and_messages_are_unique() {
+        let mut codes = HashSet::new();
+        let mut messages = HashSet::new();
+        for &(error, _, _) in CANONICAL {
+            let (_, message, code) = error.parts();
+            assert!(codes.insert(code), "duplicate code {code}");
+            assert!(messages.insert(message), "duplicate message {message}");
+        }
+    }
+
+    #[test]
+    fn every_message_is_translated() {
+        for &(error, _, _) in CANONICAL {
+            let (_, message, _) = error.parts();
+            assert_ne!(Locale::Fr.translate(message), message, "{error:?}");
+        }
+    }
+}
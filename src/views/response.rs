@@ -1,15 +1,66 @@
 use axum::{http::StatusCode, Json};
-use serde::Serialize;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use serde_json::Value;
 
-#[derive(Serialize)]
+use crate::{
+    utils::{
+        config::{self, ApiEnvelope},
+        i18n::Locale,
+    },
+    views::error_code::ErrorCode,
+};
+
 pub struct ApiResponse {
     pub success: bool,
     pub message: String,
     pub data: Option<Value>,
+    /// Machine-readable error code, set for responses built from an [`ErrorCode`]
+    pub code: Option<&'static str>,
+}
+
+impl Serialize for ApiResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_as(config::api_envelope(), serializer)
+    }
 }
 
 impl ApiResponse {
+    /// Serializes with an explicit envelope shape instead of the configured `API_ENVELOPE`.
+    pub fn serialize_as<S: Serializer>(
+        &self,
+        envelope: ApiEnvelope,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match envelope {
+            ApiEnvelope::Wrapped => {
+                let mut state = serializer.serialize_struct("ApiResponse", 4)?;
+                state.serialize_field("success", &self.success)?;
+                state.serialize_field("message", &self.message)?;
+                match self.code {
+                    Some(code) => state.serialize_field("code", code)?,
+                    None => state.skip_field("code")?,
+                }
+                state.serialize_field("data", &self.data)?;
+                state.end()
+            }
+            // Raw mode: bare data on success, a minimal error object on failure
+            ApiEnvelope::Raw if self.success => self.data.serialize(serializer),
+            ApiEnvelope::Raw => {
+                let mut state = serializer.serialize_struct("ApiError", 3)?;
+                state.serialize_field("error", &self.message)?;
+                match self.code {
+                    Some(code) => state.serialize_field("code", code)?,
+                    None => state.skip_field("code")?,
+                }
+                match &self.data {
+                    Some(errors) => state.serialize_field("errors", errors)?,
+                    None => state.skip_field("errors")?,
+                }
+                state.end()
+            }
+        }
+    }
+
     // Success response
     pub fn success(
         message: &str,
@@ -24,19 +75,30 @@ impl ApiResponse {
                 success: true,
                 message: message.to_string(),
                 data: serialized_data,
+                code: None,
             }),
         )
     }
 
-    // Failure response
-    pub fn failure(message: &str, status: Option<StatusCode>) -> (StatusCode, Json<ApiResponse>) {
-        let status_code = status.unwrap_or(StatusCode::BAD_REQUEST); // Use provided status or default to BAD_REQUEST
+    // Failure response for a canonical error condition
+    pub fn error(error: ErrorCode, locale: Locale) -> (StatusCode, Json<ApiResponse>) {
+        Self::error_with_details(error, locale, None::<()>)
+    }
+
+    // Failure response for a canonical error condition, with details (e.g. per-field errors) in `data`
+    pub fn error_with_details(
+        error: ErrorCode,
+        locale: Locale,
+        details: Option<impl Serialize>,
+    ) -> (StatusCode, Json<ApiResponse>) {
+        let (status, message, code) = error.parts();
         (
-            status_code,
+            status,
             Json(ApiResponse {
                 success: false,
-                message: message.to_string(),
-                data: None,
+                message: locale.translate(message).to_string(),
+                data: details.map(|d| serde_json::to_value(d).unwrap()),
+                code: Some(code),
             }),
         )
     }
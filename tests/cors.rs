@@ -0,0 +1,83 @@
+use std::sync::Once;
+
+use apis::routes::create_routes;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{
+        header::{
+            ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+            ACCESS_CONTROL_REQUEST_METHOD, ORIGIN, VARY,
+        },
+        Method, StatusCode,
+    },
+    response::Response,
+};
+use tower::ServiceExt;
+
+const ALLOWED_ORIGIN: &str = "https://app.example";
+
+// Settings are read once per process, so every test shares this configuration
+fn configure() {
+    static CONFIGURE: Once = Once::new();
+    CONFIGURE.call_once(|| std::env::set_var("CORS_ALLOWED_ORIGINS", ALLOWED_ORIGIN));
+}
+
+async fn send(method: Method, origin: &str) -> Response {
+    configure();
+    let mut request = Request::builder()
+        .method(method.clone())
+        .uri("/version")
+        .header(ORIGIN, origin);
+    if method == Method::OPTIONS {
+        request = request.header(ACCESS_CONTROL_REQUEST_METHOD, "GET");
+    }
+    create_routes()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn preflight_from_allowed_origin_echoes_the_origin() {
+    let response = send(Method::OPTIONS, ALLOWED_ORIGIN).await;
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+        ALLOWED_ORIGIN
+    );
+    assert!(response
+        .headers()
+        .contains_key(ACCESS_CONTROL_ALLOW_METHODS));
+}
+
+#[tokio::test]
+async fn preflight_from_disallowed_origin_gets_no_allow_origin() {
+    let response = send(Method::OPTIONS, "https://evil.example").await;
+
+    assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    assert!(!response
+        .headers()
+        .contains_key(ACCESS_CONTROL_ALLOW_METHODS));
+    assert!(response
+        .headers()
+        .get_all(VARY)
+        .iter()
+        .any(|value| value == "origin"));
+}
+
+#[tokio::test]
+async fn cross_origin_get_carries_the_allowed_origin() {
+    let response = send(Method::GET, ALLOWED_ORIGIN).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+        ALLOWED_ORIGIN
+    );
+
+    let response = send(Method::GET, "https://evil.example").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+}